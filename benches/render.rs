@@ -0,0 +1,93 @@
+// Rendering benchmark: generates a synthetic large template and times the copilot
+// binary with a single worker and with one worker per CPU.
+//
+//   cargo bench --bench render
+//
+// COPILOT_BENCH_FILES sets the number of generated templates (default 3000) and
+// COPILOT_BENCH_RUNS the number of timed runs per configuration (default 3).
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+fn env_usize(name: &str, default: usize) -> usize {
+    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+// A template tree with `files` text templates spread over nested directories, each
+// doing a little loop and filter work, plus a shared macro partial.
+fn generate_template(root: &Path, files: usize) -> std::io::Result<()> {
+    fs::write(root.join("copilot.json"), r#"{ "project_slug": "bench", "author": "Bench Author" }"#)?;
+    fs::create_dir_all(root.join("_partials"))?;
+    fs::write(
+        root.join("_partials/macros.j2"),
+        "{% macro header(name) %}# {{ name | upper }} by {{ author }}{% endmacro %}",
+    )?;
+    let project = root.join("{{ project_slug }}");
+    for i in 0..files {
+        let dir = project.join(format!("pkg{}/mod{}", i % 20, i % 7));
+        fs::create_dir_all(&dir)?;
+        let body = format!(
+            "{{% import \"macros.j2\" as m %}}{{{{ m.header(\"file{i}\") }}}}\n\
+             {{% for n in range(200) %}}{{{{ project_slug }}}}_{{{{ n }}}} = {{{{ (n * {i}) % 97 }}}} {{{{ \"item\" | title }}}}\n{{% endfor %}}"
+        );
+        fs::write(dir.join(format!("file{i}.txt")), body)?;
+    }
+    Ok(())
+}
+
+fn run_once(bin: &str, template: &Path, out: &Path, jobs: usize) -> Duration {
+    let _ = fs::remove_dir_all(out);
+    let start = Instant::now();
+    let mut child = Command::new(bin)
+        .arg(template)
+        .arg("-o").arg(out)
+        .arg("-j").arg(jobs.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .expect("failed to start copilot");
+    // Accept every default answer
+    child.stdin.take().unwrap().write_all(&b"\n".repeat(16)).unwrap();
+    let status = child.wait().expect("failed to wait for copilot");
+    assert!(status.success(), "copilot failed with {}", status);
+    start.elapsed()
+}
+
+fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort();
+    times[times.len() / 2]
+}
+
+fn main() {
+    let bin = env!("CARGO_BIN_EXE_copilot");
+    let files = env_usize("COPILOT_BENCH_FILES", 3000);
+    let runs = env_usize("COPILOT_BENCH_RUNS", 3).max(1);
+    let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+    let work = tempfile::tempdir().expect("failed to create temp dir");
+    let template = work.path().join("template");
+    let out = work.path().join("out");
+    fs::create_dir_all(&template).unwrap();
+    generate_template(&template, files).expect("failed to generate template");
+
+    println!("synthetic template: {} files, {} run(s) each", files, runs);
+    let mut configs = vec![1];
+    if cpus > 1 { configs.push(cpus); }
+    let mut baseline: Option<Duration> = None;
+    for jobs in configs {
+        let time = median((0..runs).map(|_| run_once(bin, &template, &out, jobs)).collect());
+        match baseline {
+            None => {
+                println!("jobs={:<3} median {:>8.1?}", jobs, time);
+                baseline = Some(time);
+            }
+            Some(base) => println!(
+                "jobs={:<3} median {:>8.1?}  ({:.2}x vs jobs=1)",
+                jobs, time, base.as_secs_f64() / time.as_secs_f64()
+            ),
+        }
+    }
+}
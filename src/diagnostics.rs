@@ -0,0 +1,101 @@
+use minijinja::{Error, ErrorKind};
+use serde_json::Value;
+
+// Format a MiniJinja error as a diagnostic: location (path, line, column), the failing
+// source line with the offending expression underlined, the undefined variable if any,
+// and the include/import/extends chain that led to it (outermost first).
+// `display_name` maps loader names to paths relative to the template root.
+pub fn describe_template_error(err: &Error, vars: &Value, display_name: &dyn Fn(&str) -> String) -> String {
+    // Include wrappers carry only the name of the failing template; the innermost
+    // MiniJinja error has the actual location and source.
+    let mut chain: Vec<&Error> = Vec::new();
+    let mut cause: Option<String> = None;
+    let mut current: Option<&(dyn std::error::Error + 'static)> = Some(err);
+    while let Some(e) = current {
+        match e.downcast_ref::<Error>() {
+            Some(te) => chain.push(te),
+            None => {
+                cause = Some(e.to_string());
+                break;
+            }
+        }
+        current = e.source();
+    }
+    let inner = chain.last().copied().unwrap_or(err);
+
+    let mut out = String::new();
+    match inner.detail() {
+        Some(detail) => out.push_str(&format!("error: {}: {}", inner.kind(), detail)),
+        None => out.push_str(&format!("error: {}", inner.kind())),
+    }
+    let source = inner.template_source();
+    let range = inner.range();
+    let column = match (source, &range) {
+        (Some(src), Some(r)) => src.get(..r.start).map(|before| {
+            before.rsplit('\n').next().unwrap_or("").chars().count() + 1
+        }),
+        _ => None,
+    };
+    if let Some(name) = inner.name() {
+        let mut location = display_name(name);
+        if let Some(line) = inner.line() {
+            location.push_str(&format!(":{}", line));
+            if let Some(col) = column {
+                location.push_str(&format!(":{}", col));
+            }
+        }
+        out.push_str(&format!("\n  --> {}", location));
+    }
+    if let (Some(src), Some(line)) = (source, inner.line()) {
+        if let Some(text) = src.lines().nth(line - 1) {
+            let gutter = line.to_string().len();
+            out.push_str(&format!("\n{:>w$} |", "", w = gutter));
+            out.push_str(&format!("\n{} | {}", line, text));
+            if let (Some(col), Some(r)) = (column, &range) {
+                // Underline the failing expression, clipped to the end of the line
+                let expr = src.get(r.clone()).unwrap_or("");
+                let width = expr.lines().next().unwrap_or("").chars().count().max(1);
+                out.push_str(&format!("\n{:>w$} | {}{}", "", " ".repeat(col - 1), "^".repeat(width), w = gutter));
+            }
+        }
+    }
+    if inner.kind() == ErrorKind::UndefinedError {
+        if let Some((src, r)) = source.zip(range) {
+            // Attribute spans start at the `.`; extend back to the root variable
+            let start = src[..r.start]
+                .char_indices()
+                .rev()
+                .take_while(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '.')
+                .last()
+                .map_or(r.start, |(i, _)| i);
+            if let Some(expr) = src.get(start..r.end) {
+                out.push_str(&format!("\n  = {}", undefined_note(expr, vars)));
+            }
+        }
+    }
+    // Each wrapper (outermost first) names a template that included the next one
+    for te in chain.iter().take(chain.len().saturating_sub(1)) {
+        if let Some(name) = te.name() {
+            match te.line() {
+                Some(line) => out.push_str(&format!("\n  = included from {}:{}", display_name(name), line)),
+                None => out.push_str(&format!("\n  = included from {}", display_name(name))),
+            }
+        }
+    }
+    if let Some(cause) = cause {
+        out.push_str(&format!("\n  = caused by: {}", cause));
+    }
+    out
+}
+
+// Name the undefined part of a failing expression such as `user.name`: the root
+// variable when it is not a template variable, otherwise the expression itself.
+fn undefined_note(expr: &str, vars: &Value) -> String {
+    let expr = expr.trim();
+    let root: String = expr.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+    if !root.is_empty() && vars.get(&root).is_none() {
+        format!("undefined variable: `{}` is not a template variable (in `{}`)", root, expr)
+    } else {
+        format!("undefined value: `{}`", expr)
+    }
+}
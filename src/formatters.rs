@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+use crate::manifest::Formatters;
+use crate::util::parallel_map;
+
+// What happened when one formatter ran on one file.
+enum FormatOutcome {
+    Ok,
+    Failed { program: String, file: String, message: String },
+    NotFound { program: String },
+}
+
+// Run the `_formatters` commands on every generated file under `output_dir` (the
+// staging directory). Patterns match paths relative to each root directory, or to
+// `output_dir` itself when `whole_root` is set. Formatter failures never abort
+// generation: they are printed as warnings, one per file, in path order.
+pub fn run_formatters(output_dir: &Path, formatters: &Formatters, whole_root: bool, jobs: usize) -> Result<()> {
+    if formatters.is_empty() {
+        return Ok(());
+    }
+    let mut files: Vec<(PathBuf, String, String)> = Vec::new();
+    for entry in WalkDir::new(output_dir).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() { continue; }
+        let rel = entry.path()
+            .strip_prefix(output_dir)
+            .with_context(|| format!("Failed to compute relative path: {}", entry.path().display()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let inner = if whole_root {
+            rel.clone()
+        } else {
+            match rel.split_once('/') {
+                Some((_, inner)) => inner.to_string(),
+                None => continue,
+            }
+        };
+        files.push((entry.path().to_path_buf(), rel, inner));
+    }
+    let tasks: Vec<(&PathBuf, &String, Vec<&[String]>)> = files.iter()
+        .map(|(path, rel, inner)| (path, rel, formatters.commands_for(inner)))
+        .filter(|(_, _, commands)| !commands.is_empty())
+        .collect();
+    if tasks.is_empty() {
+        return Ok(());
+    }
+    println!("Running formatters...");
+    let results = parallel_map(&tasks, jobs, |(path, rel, commands)| {
+        commands.iter()
+            .map(|command| run_formatter(output_dir, command, path, rel))
+            .collect::<Vec<_>>()
+    });
+    // A missing formatter is reported once rather than for every file
+    let mut missing: HashSet<String> = HashSet::new();
+    for outcome in results.into_iter().flatten() {
+        match outcome {
+            FormatOutcome::Ok => {}
+            FormatOutcome::Failed { program, file, message } => {
+                eprintln!("Warning: formatter '{}' failed on {}: {}", program, file, message);
+            }
+            FormatOutcome::NotFound { program } => {
+                if missing.insert(program.clone()) {
+                    eprintln!("Warning: formatter '{}' not found; skipped", program);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_formatter(cwd: &Path, command: &[String], path: &Path, rel: &str) -> FormatOutcome {
+    let file = path.to_string_lossy();
+    let mut args: Vec<String> = command.iter().map(|a| a.replace("{}", &file)).collect();
+    if !command.iter().any(|a| a.contains("{}")) {
+        args.push(file.to_string());
+    }
+    let program = command[0].clone();
+    // Run through cmd.exe on Windows so `.cmd` shims such as prettier resolve
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut c = Command::new("cmd");
+        c.arg("/C").args(&args);
+        c
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut c = Command::new(&args[0]);
+        c.args(&args[1..]);
+        c
+    };
+    let output = match cmd.current_dir(cwd).output() {
+        Ok(o) => o,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return FormatOutcome::NotFound { program },
+        Err(e) => return FormatOutcome::Failed { program, file: rel.to_string(), message: e.to_string() },
+    };
+    if output.status.success() {
+        return FormatOutcome::Ok;
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let detail = if stderr.trim().is_empty() { stdout } else { stderr };
+    let mut message = output.status.to_string();
+    let detail = detail.trim();
+    if !detail.is_empty() {
+        let indented: Vec<String> = detail.lines().map(|l| format!("  {}", l)).collect();
+        message = format!("{}\n{}", message, indented.join("\n"));
+    }
+    FormatOutcome::Failed { program, file: rel.to_string(), message }
+}
@@ -0,0 +1,316 @@
+// Hand-written glob matcher: patterns are matched against template paths, where Jinja
+// names such as '{{ project_slug }}' must match literally rather than as brace alternation.
+// Supported syntax (paths use '/' separators):
+// - '*' matches any sequence within a path segment, '?' a single character
+// - '**' as a whole segment matches any number of directories
+// - '[abc]', '[a-z]', '[!a-z]' / '[^a-z]' character classes
+// - '{a,b}' brace alternation (nesting allowed; braces without a comma are literal,
+//   so Jinja names like '{{ project_slug }}' match themselves)
+// - a leading '!' negates the pattern (see CopyFilter for last-match-wins semantics)
+use anyhow::Result;
+
+#[derive(Debug, Clone)]
+pub struct Glob {
+    pub negated: bool,
+    alternatives: Vec<String>,
+}
+
+impl Glob {
+    pub fn parse(field: &str, pat: &str) -> Result<Glob> {
+        let p = pat.trim();
+        if p.is_empty() { anyhow::bail!(format!("Invalid empty pattern in {}", field)); }
+        let (negated, body) = match p.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, p),
+        };
+        if body.is_empty() { anyhow::bail!(format!("Invalid glob pattern: {}", pat)); }
+        let body = body.replace('\\', "/");
+        let alternatives = expand_braces(&body);
+        for alt in &alternatives {
+            if !classes_are_closed(alt) {
+                anyhow::bail!(format!("Invalid glob pattern (unclosed '['): {}", pat));
+            }
+        }
+        Ok(Glob { negated, alternatives })
+    }
+
+    // True when the path matches the pattern, ignoring negation.
+    pub fn matches(&self, path: &str) -> bool {
+        self.alternatives.iter().any(|alt| pattern_matches(alt, path))
+    }
+}
+
+// Expand the first brace group containing a top-level comma, recursively.
+fn expand_braces(pat: &str) -> Vec<String> {
+    let chars: Vec<char> = pat.chars().collect();
+    for start in 0..chars.len() {
+        if chars[start] != '{' { continue; }
+        let mut depth = 0usize;
+        let mut commas = Vec::new();
+        let mut close = None;
+        for (j, c) in chars.iter().enumerate().skip(start) {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 { close = Some(j); break; }
+                }
+                ',' if depth == 1 => commas.push(j),
+                _ => {}
+            }
+        }
+        let close = match close {
+            Some(c) => c,
+            None => continue,
+        };
+        if commas.is_empty() { continue; }
+        let prefix: String = chars[..start].iter().collect();
+        let suffix: String = chars[close + 1..].iter().collect();
+        let mut bounds = vec![start];
+        bounds.extend(commas);
+        bounds.push(close);
+        let mut out = Vec::new();
+        for w in bounds.windows(2) {
+            let alt: String = chars[w[0] + 1..w[1]].iter().collect();
+            out.extend(expand_braces(&format!("{}{}{}", prefix, alt, suffix)));
+        }
+        return out;
+    }
+    vec![pat.to_string()]
+}
+
+fn classes_are_closed(pat: &str) -> bool {
+    let chars: Vec<char> = pat.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '[' {
+            match class_end(&chars, i) {
+                Some(end) => i = end,
+                None => return false,
+            }
+        }
+        i += 1;
+    }
+    true
+}
+
+// Index of the ']' closing the class opened at `start`. A ']' right after the
+// opening bracket (or after the negation mark) is a literal member.
+fn class_end(p: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if i < p.len() && (p[i] == '!' || p[i] == '^') { i += 1; }
+    if i < p.len() && p[i] == ']' { i += 1; }
+    while i < p.len() {
+        if p[i] == ']' { return Some(i); }
+        i += 1;
+    }
+    None
+}
+
+fn class_matches(p: &[char], start: usize, end: usize, c: char) -> bool {
+    let mut i = start + 1;
+    let negated = i < end && (p[i] == '!' || p[i] == '^');
+    if negated { i += 1; }
+    let mut found = false;
+    let mut first = true;
+    while i < end {
+        if !first && p[i] == ']' { break; }
+        first = false;
+        if i + 2 < end && p[i + 1] == '-' {
+            if p[i] <= c && c <= p[i + 2] { found = true; }
+            i += 3;
+        } else {
+            if p[i] == c { found = true; }
+            i += 1;
+        }
+    }
+    found != negated
+}
+
+fn segment_matches(pat: &str, s: &str) -> bool {
+    let p: Vec<char> = pat.chars().collect();
+    let t: Vec<char> = s.chars().collect();
+    let (mut pi, mut ti) = (0usize, 0usize);
+    // Position after the last '*' and the text index it is currently anchored to
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() {
+            match p[pi] {
+                '*' => {
+                    star = Some((pi + 1, ti));
+                    pi += 1;
+                    continue;
+                }
+                '?' => {
+                    pi += 1;
+                    ti += 1;
+                    continue;
+                }
+                '[' => {
+                    if let Some(end) = class_end(&p, pi) {
+                        if class_matches(&p, pi, end, t[ti]) {
+                            pi = end + 1;
+                            ti += 1;
+                            continue;
+                        }
+                    } else if t[ti] == '[' {
+                        pi += 1;
+                        ti += 1;
+                        continue;
+                    }
+                }
+                c => {
+                    if c == t[ti] {
+                        pi += 1;
+                        ti += 1;
+                        continue;
+                    }
+                }
+            }
+        }
+        // Mismatch: let the last '*' absorb one more character
+        match star {
+            Some((sp, st)) => {
+                pi = sp;
+                ti = st + 1;
+                star = Some((sp, st + 1));
+            }
+            None => return false,
+        }
+    }
+    while pi < p.len() && p[pi] == '*' { pi += 1; }
+    pi == p.len()
+}
+
+pub fn pattern_matches(pat: &str, path: &str) -> bool {
+    let psegs: Vec<&str> = pat.split('/').collect();
+    let ssegs: Vec<&str> = path.split('/').collect();
+    fn rec(p: &[&str], s: &[&str]) -> bool {
+        if p.is_empty() { return s.is_empty(); }
+        if p[0] == "**" {
+            if p.len() == 1 { return true; } // matches the rest
+            for skip in 0..=s.len() {
+                if rec(&p[1..], &s[skip..]) { return true; }
+            }
+            return false;
+        }
+        if s.is_empty() { return false; }
+        if segment_matches(p[0], s[0]) { return rec(&p[1..], &s[1..]); }
+        false
+    }
+    rec(&psegs, &ssegs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob_matches(pat: &str, path: &str) -> bool {
+        Glob::parse("test", pat).unwrap().matches(path)
+    }
+
+    // Single-segment cases agree with Python's `fnmatch.fnmatchcase`, except `[^...]`,
+    // which fnmatch reads literally but we accept as negation like gitignore does.
+    #[test]
+    fn matches_like_fnmatch() {
+        let cases: &[(&str, &str, bool)] = &[
+            ("*", "abc", true),
+            ("*", "", true),
+            ("*.py", "setup.py", true),
+            ("*.py", "setup.pyc", false),
+            ("a*c", "abbbc", true),
+            ("a*c", "abcd", false),
+            ("*a*a*", "banana", true),
+            ("?", "a", true),
+            ("?", "", false),
+            ("??", "a", false),
+            ("a?c", "abc", true),
+            ("[a-z]", "m", true),
+            ("[a-z]", "M", false),
+            ("[a-z]", "-", false),
+            ("[abc]x", "bx", true),
+            ("[!a]", "b", true),
+            ("[!a]", "a", false),
+            ("[^a]", "b", true),
+            ("[^a]", "a", false),
+            ("[]a]", "]", true),
+            ("[]a]", "a", true),
+            ("[]a]", "b", false),
+            ("[!]a]", "]", false),
+            ("[!]a]", "b", true),
+            ("[a-]", "-", true),
+            ("x[0-9][0-9]", "x42", true),
+            ("x[0-9][0-9]", "x4a", false),
+            ("README.md", "README.md", true),
+            ("README.md", "readme.md", false),
+        ];
+        for (pat, path, expected) in cases {
+            assert_eq!(glob_matches(pat, path), *expected, "pattern {:?} on {:?}", pat, path);
+        }
+    }
+
+    #[test]
+    fn star_and_question_stay_within_a_segment() {
+        assert!(!glob_matches("*", "a/b"));
+        assert!(!glob_matches("a?b", "a/b"));
+        assert!(glob_matches("*/*.rs", "src/main.rs"));
+        assert!(!glob_matches("*.rs", "src/main.rs"));
+    }
+
+    #[test]
+    fn double_star() {
+        let cases: &[(&str, &str, bool)] = &[
+            // leading
+            ("**/*.rs", "main.rs", true),
+            ("**/*.rs", "src/bin/main.rs", true),
+            ("**/*.rs", "src/main.py", false),
+            // middle
+            ("src/**/mod.rs", "src/mod.rs", true),
+            ("src/**/mod.rs", "src/a/b/mod.rs", true),
+            ("src/**/mod.rs", "lib/a/mod.rs", false),
+            // trailing
+            ("tests/**", "tests/a.py", true),
+            ("tests/**", "tests/unit/deep/a.py", true),
+            ("tests/**", "src/tests.py", false),
+            ("**", "any/path/at/all", true),
+        ];
+        for (pat, path, expected) in cases {
+            assert_eq!(glob_matches(pat, path), *expected, "pattern {:?} on {:?}", pat, path);
+        }
+    }
+
+    #[test]
+    fn braces() {
+        let cases: &[(&str, &str, bool)] = &[
+            ("*.{js,ts}", "app.ts", true),
+            ("*.{js,ts}", "app.js", true),
+            ("*.{js,ts}", "app.py", false),
+            ("{src,tests}/**", "tests/a/b.rs", true),
+            ("{src,tests}/**", "docs/a.md", false),
+            // nested groups
+            ("*.{c,{h,hpp}}", "x.hpp", true),
+            ("*.{c,{h,hpp}}", "x.h", true),
+            ("*.{c,{h,hpp}}", "x.cpp", false),
+            ("a{b,c{d,e}}f", "acef", true),
+            ("a{b,c{d,e}}f", "abf", true),
+            ("a{b,c{d,e}}f", "acf", false),
+            // braces without a comma are literal, so Jinja names match themselves
+            ("{{ project_slug }}/**", "{{ project_slug }}/src/lib.rs", true),
+            ("{{ project_slug }}/**", "demo/src/lib.rs", false),
+            ("{x}", "{x}", true),
+        ];
+        for (pat, path, expected) in cases {
+            assert_eq!(glob_matches(pat, path), *expected, "pattern {:?} on {:?}", pat, path);
+        }
+    }
+
+    #[test]
+    fn negation_flag_and_errors() {
+        let g = Glob::parse("test", "!tests/conftest.py").unwrap();
+        assert!(g.negated);
+        assert!(g.matches("tests/conftest.py"));
+        assert!(Glob::parse("test", "[abc").is_err());
+        assert!(Glob::parse("test", "").is_err());
+        assert!(Glob::parse("test", "!").is_err());
+    }
+}
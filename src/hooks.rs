@@ -2,12 +2,19 @@ use anyhow::{Context, Result};
 use mlua::{Lua, Table, Value as LuaValue};
 use serde_json::Value as JsonValue;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use crate::manifest::{QuestionEdits, VarDef, VarKind};
 
 #[derive(Debug, Default)]
 pub struct HookResult {
     pub updated_vars: Option<JsonValue>,
     pub created_files: Vec<(PathBuf, String)>,
+    pub questions: QuestionEdits,
+    pub errors: Vec<String>,
 }
 
 fn load_hook_script(root: &Path, name: &str) -> Result<Option<String>> {
@@ -25,27 +32,34 @@ fn json_to_lua_table<'lua>(lua: &'lua Lua, json: &JsonValue) -> Result<Table<'lu
     let table = lua.create_table()?;
     if let Some(obj) = json.as_object() {
         for (k, v) in obj.iter() {
-            match v {
-                JsonValue::String(s) => {
-                    table.set(k.as_str(), s.as_str())?;
-                }
-                JsonValue::Bool(b) => {
-                    table.set(k.as_str(), *b)?;
-                }
-                JsonValue::Number(n) => {
-                    if let Some(i) = n.as_i64() {
-                        table.set(k.as_str(), i)?;
-                    } else if let Some(f) = n.as_f64() {
-                        table.set(k.as_str(), f)?;
-                    }
-                }
-                _ => {}
+            let lv = json_scalar_to_lua(lua, v)?;
+            if lv != LuaValue::Nil {
+                table.set(k.as_str(), lv)?;
             }
         }
     }
     Ok(table)
 }
 
+// Convert strings, booleans and numbers; other JSON values map to nil.
+fn json_scalar_to_lua<'lua>(lua: &'lua Lua, json: &JsonValue) -> Result<LuaValue<'lua>> {
+    let val = match json {
+        JsonValue::String(s) => LuaValue::String(lua.create_string(s)?),
+        JsonValue::Bool(b) => LuaValue::Boolean(*b),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                LuaValue::Integer(i)
+            } else if let Some(f) = n.as_f64() {
+                LuaValue::Number(f)
+            } else {
+                LuaValue::Nil
+            }
+        }
+        _ => LuaValue::Nil,
+    };
+    Ok(val)
+}
+
 fn lua_value_to_json(val: LuaValue) -> Option<JsonValue> {
     match val {
         LuaValue::Nil => None,
@@ -66,6 +80,101 @@ fn lua_value_to_json(val: LuaValue) -> Option<JsonValue> {
     }
 }
 
+fn lua_string_list(tbl: &Table) -> Vec<String> {
+    tbl.clone().sequence_values::<String>().flatten().collect()
+}
+
+// Build a variable definition from a Lua question table, e.g.
+// { name = "team", choices = { "core", "infra" }, labels = { core = "Core team" }, default = "infra" }
+// The kind is taken from `kind` ("string", "bool", "number", "choice") when present,
+// otherwise inferred from `choices` or the type of `default`.
+fn lua_question_to_var_def(item: &Table) -> Result<VarDef> {
+    let name: String = item.get::<_, Option<String>>("name")?
+        .ok_or_else(|| anyhow::anyhow!("pre_prompt question is missing 'name'"))?;
+    let default = item.get::<_, LuaValue>("default").ok().and_then(lua_value_to_json);
+    let choices: Vec<String> = match item.get::<_, Option<Table>>("choices")? {
+        Some(t) => lua_string_list(&t),
+        None => Vec::new(),
+    };
+    let labels: Option<BTreeMap<String, String>> = item.get::<_, Option<Table>>("labels")?
+        .map(|t| t.pairs::<String, String>().flatten().collect());
+    let kind_name: Option<String> = item.get("kind")?;
+    let kind = match kind_name.as_deref() {
+        Some("choice") => VarKind::Choice(choices.clone()),
+        Some("string") => VarKind::String,
+        Some("bool") => VarKind::Bool,
+        Some("number") => VarKind::Number,
+        Some(other) => anyhow::bail!(format!("Unsupported kind '{}' for question '{}'", other, name)),
+        None if !choices.is_empty() => VarKind::Choice(choices.clone()),
+        None => match &default {
+            Some(JsonValue::Bool(_)) => VarKind::Bool,
+            Some(JsonValue::Number(_)) => VarKind::Number,
+            _ => VarKind::String,
+        },
+    };
+    let default = match &kind {
+        VarKind::Choice(choices) => {
+            if choices.is_empty() {
+                anyhow::bail!(format!("Choice question '{}' has no choices", name));
+            }
+            match default {
+                Some(JsonValue::String(d)) if choices.contains(&d) => Some(JsonValue::String(d)),
+                _ => choices.first().map(|s| JsonValue::String(s.clone())),
+            }
+        }
+        _ => default,
+    };
+    let choice_labels = if matches!(kind, VarKind::Choice(_)) { labels } else { None };
+    Ok(VarDef { name, kind, default, choice_labels, hidden: false })
+}
+
+fn lua_question_edits(tbl: &Table) -> Result<QuestionEdits> {
+    let mut edits = QuestionEdits::default();
+    if let Some(hide) = tbl.get::<_, Option<Table>>("hide")? {
+        edits.hide = lua_string_list(&hide);
+    }
+    if let Some(add) = tbl.get::<_, Option<Table>>("add")? {
+        for item in add.sequence_values::<Table>() {
+            edits.add.push(lua_question_to_var_def(&item?)?);
+        }
+    }
+    if let Some(order) = tbl.get::<_, Option<Table>>("order")? {
+        edits.order = Some(lua_string_list(&order));
+    }
+    Ok(edits)
+}
+
+// Restrict `require` to modules under the template's `hooks/lib/` directory,
+// e.g. `require("lib.util")` loads `hooks/lib/util.lua` (or `hooks/lib/util/init.lua`).
+// C modules and the host's LUA_PATH are never consulted.
+const REQUIRE_SETUP: &str = r#"
+local hooks_dir = ...
+local search_path = hooks_dir .. "/?.lua;" .. hooks_dir .. "/?/init.lua"
+package.path = ""
+package.cpath = ""
+package.searchers = {
+  package.searchers[1],
+  function(name)
+    if not name:match("^[%w_%.]+$") or (name ~= "lib" and name:sub(1, 4) ~= "lib.") then
+      return "\n\tno module '" .. name .. "' in hooks/lib"
+    end
+    local path, err = package.searchpath(name, search_path)
+    if not path then return err end
+    local chunk, load_err = loadfile(path)
+    if not chunk then error(load_err, 2) end
+    return chunk, path
+  end,
+}
+"#;
+
+fn new_lua(root: &Path) -> Result<Lua> {
+    let lua = Lua::new();
+    let hooks_dir = root.join("hooks").to_string_lossy().to_string();
+    lua.load(REQUIRE_SETUP).call::<_, ()>(hooks_dir)
+        .with_context(|| "Failed to configure Lua module loader")?;
+    Ok(lua)
+}
+
 fn run_hook(
     root: &Path,
     script_name: &str,
@@ -76,7 +185,7 @@ fn run_hook(
         Some(s) => s,
         None => return Ok(HookResult::default()),
     };
-    let lua = Lua::new();
+    let lua = new_lua(root)?;
     let globals = lua.globals();
     let vars_tbl = json_to_lua_table(&lua, vars)?;
     let ctx_tbl = json_to_lua_table(&lua, ctx)?;
@@ -99,25 +208,243 @@ fn run_hook(
                 }
             }
         }
+        if let Ok(errors_tbl) = t.get::<_, Table>("errors") {
+            result.errors = lua_string_list(&errors_tbl);
+        }
+        if let Ok(questions_tbl) = t.get::<_, Table>("questions") {
+            result.questions = lua_question_edits(&questions_tbl)
+                .with_context(|| format!("Invalid 'questions' returned by {}", script_name))?;
+        }
     }
     Ok(result)
 }
 
-pub fn run_pre_prompt(root: &Path, current_vars: &JsonValue) -> Result<Option<JsonValue>> {
+// Locate an executable hook for `stage` next to the Lua hooks:
+// - `<stage>.sh` is run through `sh` on every platform
+// - `<stage>.bat` / `<stage>.cmd` are run through `cmd /C` on Windows
+// - an extension-less `<stage>` file with an executable bit is run directly on Unix
+fn find_shell_hook(root: &Path, stage: &str) -> Option<Command> {
+    let dir = root.join("hooks");
+    let sh = dir.join(format!("{}.sh", stage));
+    if sh.is_file() {
+        let mut cmd = Command::new("sh");
+        cmd.arg(sh);
+        return Some(cmd);
+    }
+    #[cfg(windows)]
+    for ext in ["bat", "cmd"] {
+        let script = dir.join(format!("{}.{}", stage, ext));
+        if script.is_file() {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(script);
+            return Some(cmd);
+        }
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let exe = dir.join(stage);
+        if let Ok(md) = fs::metadata(&exe) {
+            if md.is_file() && md.permissions().mode() & 0o111 != 0 {
+                return Some(Command::new(exe));
+            }
+        }
+    }
+    None
+}
+
+// Environment variable name for an answer: `project_slug` -> `COPILOT_VAR_PROJECT_SLUG`.
+fn env_var_name(name: &str) -> String {
+    let upper: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("COPILOT_VAR_{}", upper)
+}
+
+// Run an executable hook for `stage` with `cwd` as working directory.
+// Answers are exported as `COPILOT_VAR_*` variables, and `COPILOT_CONTEXT` points to a
+// JSON file holding `{ "vars": ..., "ctx": ... }`. Output is captured and echoed;
+// a non-zero exit status aborts generation like a failing Lua hook.
+fn run_shell_hook(root: &Path, stage: &str, vars: &JsonValue, ctx: &JsonValue, cwd: &Path) -> Result<()> {
+    let mut cmd = match find_shell_hook(root, stage) {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+    let mut context_file = tempfile::NamedTempFile::new()
+        .with_context(|| "Failed to create hook context file")?;
+    let context = serde_json::json!({ "vars": vars, "ctx": ctx });
+    context_file.write_all(serde_json::to_string_pretty(&context)?.as_bytes())?;
+    context_file.flush()?;
+
+    cmd.current_dir(cwd)
+        .env("COPILOT_STAGE", stage)
+        .env("COPILOT_CONTEXT", context_file.path());
+    if let Some(output) = ctx.get("output").and_then(|v| v.as_str()) {
+        cmd.env("COPILOT_OUTPUT", output);
+    }
+    if let Some(obj) = vars.as_object() {
+        for (k, v) in obj.iter() {
+            let val = match v {
+                JsonValue::String(s) => s.clone(),
+                JsonValue::Bool(_) | JsonValue::Number(_) => v.to_string(),
+                _ => continue,
+            };
+            cmd.env(env_var_name(k), val);
+        }
+    }
+    let out = cmd.output().with_context(|| format!("Failed to execute {} hook", stage))?;
+    print!("{}", String::from_utf8_lossy(&out.stdout));
+    eprint!("{}", String::from_utf8_lossy(&out.stderr));
+    // The output has just been echoed, so the error only carries the status
+    if !out.status.success() {
+        anyhow::bail!(format!("{} hook failed ({})", stage, out.status));
+    }
+    Ok(())
+}
+
+// Run the Lua hook for `stage` (if any), then its executable counterpart (if any).
+fn run_stage(root: &Path, stage: &str, vars: &JsonValue, ctx: &JsonValue, cwd: &Path) -> Result<HookResult> {
+    let res = run_hook(root, &format!("{}.lua", stage), vars, ctx)?;
+    run_shell_hook(root, stage, vars, ctx, cwd)?;
+    Ok(res)
+}
+
+pub fn run_pre_prompt(root: &Path, current_vars: &JsonValue) -> Result<HookResult> {
     let ctx = serde_json::json!({ "stage": "pre_prompt" });
-    let res = run_hook(root, "pre_prompt.lua", current_vars, &ctx)?;
-    Ok(res.updated_vars)
+    let res = run_stage(root, "pre_prompt", current_vars, &ctx, root)?;
+    Ok(res)
 }
 
-pub fn run_pre_gen(root: &Path, vars: &JsonValue, output: &Path) -> Result<HookResult> {
+pub fn run_post_prompt(root: &Path, answers: &JsonValue) -> Result<HookResult> {
+    let ctx = serde_json::json!({ "stage": "post_prompt" });
+    let res = run_stage(root, "post_prompt", answers, &ctx, root)?;
+    Ok(res)
+}
+
+pub fn run_pre_gen(root: &Path, vars: &JsonValue, output: &Path, project_dir: &Path) -> Result<HookResult> {
     let ctx = serde_json::json!({ "stage": "pre_gen_project", "output": output.to_string_lossy() });
-    let res = run_hook(root, "pre_gen_project.lua", vars, &ctx)?;
+    let res = run_stage(root, "pre_gen_project", vars, &ctx, project_dir)?;
     Ok(res)
 }
 
-pub fn run_post_gen(root: &Path, vars: &JsonValue, output: &Path) -> Result<HookResult> {
+pub fn run_post_gen(root: &Path, vars: &JsonValue, output: &Path, project_dir: &Path) -> Result<HookResult> {
     let ctx =
         serde_json::json!({ "stage": "post_gen_project", "output": output.to_string_lossy() });
-    let res = run_hook(root, "post_gen_project.lua", vars, &ctx)?;
+    let res = run_stage(root, "post_gen_project", vars, &ctx, project_dir)?;
+    Ok(res)
+}
+
+pub fn run_post_copy(root: &Path, vars: &JsonValue, output: &Path, project_dir: &Path) -> Result<HookResult> {
+    let ctx = serde_json::json!({ "stage": "post_copy", "output": output.to_string_lossy() });
+    let res = run_stage(root, "post_copy", vars, &ctx, project_dir)?;
     Ok(res)
 }
+
+// Outcome of a per-file hook: keep the file with (possibly transformed) content, or skip it.
+pub enum FileHookOutcome {
+    Keep(String),
+    Skip,
+}
+
+// Per-file hooks run by the renderer for every rendered (non-raw) file:
+// - `pre_render_file.lua` sees the template source before rendering
+// - `post_render_file.lua` sees the rendered output before it is written
+// Each script is evaluated once per file with `vars` and `ctx` (`stage`, `path`, `content`),
+// and may return `{ skip = true }` or `{ content = "..." }`.
+pub struct FileHooks {
+    // Shared by the render workers; hook invocations run one at a time
+    lua: Option<Mutex<Lua>>,
+    pre_render: Option<String>,
+    post_render: Option<String>,
+}
+
+impl FileHooks {
+    pub fn load(root: &Path) -> Result<FileHooks> {
+        let pre_render = load_hook_script(root, "pre_render_file.lua")?;
+        let post_render = load_hook_script(root, "post_render_file.lua")?;
+        let lua = if pre_render.is_some() || post_render.is_some() { Some(Mutex::new(new_lua(root)?)) } else { None };
+        Ok(FileHooks { lua, pre_render, post_render })
+    }
+
+    pub fn pre_render(&self, vars: &JsonValue, path: &str, content: String) -> Result<FileHookOutcome> {
+        self.run(self.pre_render.as_deref(), "pre_render_file", vars, path, content)
+    }
+
+    pub fn post_render(&self, vars: &JsonValue, path: &str, content: String) -> Result<FileHookOutcome> {
+        self.run(self.post_render.as_deref(), "post_render_file", vars, path, content)
+    }
+
+    fn run(&self, script: Option<&str>, stage: &str, vars: &JsonValue, path: &str, content: String) -> Result<FileHookOutcome> {
+        let (lua, script) = match (&self.lua, script) {
+            (Some(lua), Some(script)) => (lua, script),
+            _ => return Ok(FileHookOutcome::Keep(content)),
+        };
+        let lua = lua.lock().map_err(|_| anyhow::anyhow!("{} hook state is poisoned", stage))?;
+        let lua = &*lua;
+        let globals = lua.globals();
+        globals.set("vars", json_to_lua_table(lua, vars)?)?;
+        let ctx_tbl = lua.create_table()?;
+        ctx_tbl.set("stage", stage)?;
+        ctx_tbl.set("path", path)?;
+        ctx_tbl.set("content", content.as_str())?;
+        globals.set("ctx", ctx_tbl)?;
+        let val: LuaValue = lua.load(script).eval()
+            .with_context(|| format!("{} hook failed for {}", stage, path))?;
+        if let LuaValue::Table(t) = val {
+            if t.get::<_, Option<bool>>("skip")?.unwrap_or(false) {
+                return Ok(FileHookOutcome::Skip);
+            }
+            if let Some(new_content) = t.get::<_, Option<String>>("content")? {
+                return Ok(FileHookOutcome::Keep(new_content));
+            }
+        }
+        Ok(FileHookOutcome::Keep(content))
+    }
+}
+
+// Per-variable validators: `hooks/validate.lua` may define `validate_<var>(value, answers)`
+// functions. A validator returns nil/true to accept the value, or a message string
+// (or false) to reject it and re-prompt. The script is loaded once and reused.
+pub struct Validators {
+    lua: Lua,
+}
+
+impl Validators {
+    pub fn load(root: &Path) -> Result<Option<Validators>> {
+        let script = match load_hook_script(root, "validate.lua")? {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+        let lua = new_lua(root)?;
+        {
+            let globals = lua.globals();
+            let ctx_tbl = json_to_lua_table(&lua, &serde_json::json!({ "stage": "validate" }))?;
+            globals.set("ctx", ctx_tbl)?;
+            lua.load(&script).exec()
+                .with_context(|| "Failed to load hook script: validate.lua")?;
+        }
+        Ok(Some(Validators { lua }))
+    }
+
+    pub fn validate(&self, name: &str, value: &JsonValue, answers: &JsonValue) -> Result<Option<String>> {
+        let func: Option<mlua::Function> = self.lua.globals().get(format!("validate_{}", name))?;
+        let func = match func {
+            Some(f) => f,
+            None => return Ok(None),
+        };
+        let lua_value = json_scalar_to_lua(&self.lua, value)?;
+        let answers_tbl = json_to_lua_table(&self.lua, answers)?;
+        let ret: LuaValue = func.call((lua_value, answers_tbl))
+            .with_context(|| format!("Validator validate_{} failed", name))?;
+        let msg = match ret {
+            LuaValue::Nil | LuaValue::Boolean(true) => None,
+            LuaValue::Boolean(false) => Some(format!("rejected by validate_{}", name)),
+            LuaValue::String(s) => Some(s.to_str()?.to_string()),
+            other => anyhow::bail!(format!(
+                "Validator validate_{} returned a value of type {}; expected nil, a boolean or a message string",
+                name, other.type_name()
+            )),
+        };
+        Ok(msg)
+    }
+}
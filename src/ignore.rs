@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use crate::glob::Glob;
+
+pub const IGNORE_FILE_NAME: &str = ".copilotignore";
+
+// One parsed line of an ignore file.
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    glob: Glob,
+    dir_only: bool,
+}
+
+// Exclusion rules in gitignore syntax:
+// - blank lines and lines starting with '#' are skipped
+// - a leading '!' re-includes paths excluded by earlier lines (last match wins)
+// - a trailing '/' only matches directories
+// - patterns containing '/' are relative to the file's directory, others match at any depth
+// - a path inside an excluded directory is excluded and cannot be re-included
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreFile {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreFile {
+    pub fn load(path: &Path) -> Result<Option<IgnoreFile>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read ignore file: {}", path.display()))?;
+        let parsed = IgnoreFile::parse(&text)
+            .with_context(|| format!("Invalid ignore file: {}", path.display()))?;
+        Ok(Some(parsed))
+    }
+
+    pub fn parse(text: &str) -> Result<IgnoreFile> {
+        let mut rules = Vec::new();
+        for line in text.lines() {
+            let line = line.trim_end();
+            if line.trim().is_empty() || line.starts_with('#') { continue; }
+            let (negated, body) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line.strip_prefix('\\').unwrap_or(line)),
+            };
+            let dir_only = body.ends_with('/');
+            let body = body.trim_end_matches('/');
+            if body.is_empty() { continue; }
+            let pattern = if body.contains('/') {
+                body.trim_start_matches('/').to_string()
+            } else {
+                format!("**/{}", body)
+            };
+            let pattern = if negated { format!("!{}", pattern) } else { pattern };
+            rules.push(IgnoreRule { glob: Glob::parse(IGNORE_FILE_NAME, &pattern)?, dir_only });
+        }
+        Ok(IgnoreFile { rules })
+    }
+
+    // `rel` is a file path relative to the directory holding the ignore file.
+    pub fn is_ignored(&self, rel: &str) -> bool {
+        let rel = rel.replace('\\', "/");
+        let segs: Vec<&str> = rel.split('/').collect();
+        for end in 1..=segs.len() {
+            let is_dir = end < segs.len();
+            if self.matches(&segs[..end].join("/"), is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir { continue; }
+            if rule.glob.matches(path) { ignored = !rule.glob.negated; }
+        }
+        ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IgnoreFile;
+
+    fn ignore(text: &str) -> IgnoreFile {
+        IgnoreFile::parse(text).unwrap()
+    }
+
+    #[test]
+    fn comments_blank_lines_and_escapes() {
+        let ig = ignore("# a comment\n\n   \n\\#notes.txt\n\\!bang.txt\n");
+        assert!(!ig.is_ignored("# a comment"));
+        assert!(ig.is_ignored("#notes.txt"));
+        assert!(ig.is_ignored("docs/#notes.txt"));
+        assert!(ig.is_ignored("!bang.txt"));
+        assert!(!ig.is_ignored("notes.txt"));
+        assert!(!ig.is_ignored("bang.txt"));
+    }
+
+    #[test]
+    fn dir_only_patterns() {
+        let ig = ignore("build/\n");
+        assert!(ig.is_ignored("build/out.o"));
+        assert!(ig.is_ignored("src/build/gen/out.o"));
+        // A file named like the directory is kept
+        assert!(!ig.is_ignored("build"));
+        assert!(!ig.is_ignored("src/build"));
+    }
+
+    #[test]
+    fn anchored_and_unanchored_patterns() {
+        let ig = ignore("*.log\n/TODO\ndocs/*.tmp\n");
+        // No '/': matches the name at any depth
+        assert!(ig.is_ignored("app.log"));
+        assert!(ig.is_ignored("a/b/app.log"));
+        // Leading or inner '/': relative to the ignore file's directory
+        assert!(ig.is_ignored("TODO"));
+        assert!(!ig.is_ignored("src/TODO"));
+        assert!(ig.is_ignored("docs/draft.tmp"));
+        assert!(!ig.is_ignored("src/docs/draft.tmp"));
+        assert!(!ig.is_ignored("docs/sub/draft.tmp"));
+        assert!(ig.is_ignored("windows\\path\\app.log"));
+    }
+
+    #[test]
+    fn negation_and_excluded_directories() {
+        let ig = ignore("*.md\n!README.md\n");
+        assert!(ig.is_ignored("CHANGES.md"));
+        assert!(!ig.is_ignored("README.md"));
+        assert!(!ig.is_ignored("docs/README.md"));
+
+        // A later pattern overrides a negation again
+        let ig = ignore("*.md\n!*.md\ndocs/*.md\n");
+        assert!(!ig.is_ignored("README.md"));
+        assert!(ig.is_ignored("docs/guide.md"));
+
+        // Files inside an excluded directory cannot be re-included
+        let ig = ignore("vendor/\n!vendor/keep.txt\n");
+        assert!(ig.is_ignored("vendor/keep.txt"));
+        assert!(ig.is_ignored("vendor/lib/a.rs"));
+        let ig = ignore("cache\n!cache/keep\n");
+        assert!(ig.is_ignored("cache/keep"));
+    }
+}
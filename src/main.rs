@@ -2,36 +2,52 @@ use anyhow::{Result, Context};
 use serde_json::{Value, Number};
 
 mod manifest;
+mod glob;
+mod ignore;
 mod template_loader;
 mod renderer;
 mod hooks;
 mod vcs;
 mod util;
+mod diagnostics;
+mod formatters;
 
-use manifest::{load_manifest, Manifest, VarKind};
+use manifest::{load_manifest, Manifest, VarDef, VarKind};
 use template_loader::{load_template, template_root, copy_to_temp_root};
 use dialoguer::{Input, Confirm};
 use std::collections::BTreeMap;
 use std::io;
 use std::io::IsTerminal;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::env;
-use hooks::{run_pre_prompt, run_pre_gen, run_post_gen};
-use crate::util::{sanitize_slug_python, is_safe_rel_path, safe_resolve_under_canon};
-use manifest::CopyFilter;
+use hooks::{run_pre_prompt, run_post_prompt, run_pre_gen, run_post_gen, run_post_copy, FileHooks, Validators};
+use crate::util::{is_safe_rel_path, safe_resolve_under_canon};
+use manifest::{CopyFilter, FileModes, PathRules};
+use renderer::RenderOptions;
+
+// Command-line options
+struct Args {
+    source: String,
+    output: PathBuf,
+    // Print warnings about files handled specially (e.g. binary files copied verbatim)
+    verbose: bool,
+    // Worker threads used to render and write files
+    jobs: usize,
+}
 
 fn main() -> Result<()> {
-    let (source, output) = parse_args().map_err(|e| {
+    let args = parse_args().map_err(|e| {
         eprintln!("Error: {}", e);
         e
     })?;
-    run(source, output).map_err(|e| {
+    run(args).map_err(|e| {
         eprintln!("Error: {}", e);
         e
     })
 }
 
-fn run(source: String, output: PathBuf) -> Result<()> {
+fn run(args: Args) -> Result<()> {
+    let Args { source, output, verbose, jobs } = args;
     let ts = load_template(&source)?;
     let original_root = template_root(&ts);
     // Auto-detect and prepare Git submodules in source repository (best-effort)
@@ -52,7 +68,7 @@ fn run(source: String, output: PathBuf) -> Result<()> {
     // Step a) copy template to a temp directory
     let (temp_root_guard, temp_root) = copy_to_temp_root(original_root)?;
     let root = temp_root.as_path();
-    let manifest: Manifest = load_manifest(root)?;
+    let mut manifest: Manifest = load_manifest(root)?;
     let mut vars: BTreeMap<String, Value> = BTreeMap::new();
 
     // Pre-fill defaults
@@ -62,11 +78,18 @@ fn run(source: String, output: PathBuf) -> Result<()> {
         }
     }
 
-    // Run pre_prompt.lua to update defaults
+    // Run pre_prompt.lua to update defaults and the question list
     let initial_vars_json = serde_json::Value::Object(
         vars.iter().map(|(k,v)| (k.clone(), v.clone())).collect()
     );
-    if let Some(updated) = run_pre_prompt(root, &initial_vars_json)? {
+    let pre_prompt = run_pre_prompt(root, &initial_vars_json)?;
+    for spec in &pre_prompt.questions.add {
+        if let Some(default) = &spec.default {
+            vars.insert(spec.name.clone(), default.clone());
+        }
+    }
+    manifest.apply_question_edits(&pre_prompt.questions);
+    if let Some(updated) = pre_prompt.updated_vars {
         if let Some(obj) = updated.as_object() {
             for (k, v) in obj.iter() { vars.insert(k.clone(), v.clone()); }
         }
@@ -77,112 +100,38 @@ fn run(source: String, output: PathBuf) -> Result<()> {
 
     // One-by-one TUI prompts (fallback to stdin when not a TTY)
     let is_tty = io::stdin().is_terminal();
+    let validators = Validators::load(root)?;
+    let mut eof = false;
+    // Validators see only the questions answered so far, in prompt order
+    let mut answers = serde_json::Map::new();
     for spec in &manifest.variables {
-        match &spec.kind {
-            VarKind::String => {
-                let def = vars.get(&spec.name).and_then(|v| v.as_str()).map(|s| s.to_string());
-                let prompt = format!("Enter {}:", spec.name);
-                let input: String = if is_tty {
-                    if let Some(d) = def.clone() {
-                        Input::new().with_prompt(format!("{} (default: {})", prompt, d)).allow_empty(true).interact_text()?
-                    } else {
-                        Input::new().with_prompt(&prompt).interact_text()?
-                    }
-                } else {
-                    println!("{}{}", prompt, def.as_ref().map(|d| format!(" (default: {})", d)).unwrap_or_default());
-                    let mut buf = String::new();
-                    io::stdin().read_line(&mut buf)?;
-                    buf.trim_end().to_string()
-                };
-                let final_value = if input.is_empty() { def.unwrap_or_default() } else { input };
-                vars.insert(spec.name.clone(), Value::String(final_value));
-            }
-            VarKind::Bool => {
-                let def = vars.get(&spec.name).and_then(|v| v.as_bool()).unwrap_or(false);
-                let val = if is_tty {
-                    Confirm::new()
-                        .with_prompt(format!("{}?", spec.name))
-                        .default(def)
-                        .interact()?
-                } else {
-                    println!("{}? (y/n, default: {})", spec.name, if def { "y" } else { "n" });
-                    let mut buf = String::new();
-                    io::stdin().read_line(&mut buf)?;
-                    let s = buf.trim().to_ascii_lowercase();
-                    if s.is_empty() { def } else { s.starts_with('y') }
-                };
-                vars.insert(spec.name.clone(), Value::Bool(val));
-            }
-            VarKind::Number => {
-                let def = vars.get(&spec.name).and_then(|v| v.as_i64());
-                let prompt = format!("Enter number for {}:", spec.name);
-                let input: String = if is_tty {
-                    if let Some(d) = def {
-                        Input::new().with_prompt(format!("{} (default: {})", prompt, d)).allow_empty(true).interact_text()?
-                    } else {
-                        Input::new().with_prompt(&prompt).interact_text()?
+        if spec.hidden { continue; }
+        loop {
+            let value = prompt_var(spec, &vars, is_tty, &mut eof)?;
+            if let Some(validators) = &validators {
+                if let Some(msg) = validators.validate(&spec.name, &value, &serde_json::Value::Object(answers.clone()))? {
+                    if eof {
+                        anyhow::bail!(format!("Invalid value for {}: {}", spec.name, msg));
                     }
-                } else {
-                    println!("{}{}", prompt, def.map(|d| format!(" (default: {})", d)).unwrap_or_default());
-                    let mut buf = String::new();
-                    io::stdin().read_line(&mut buf)?;
-                    buf.trim_end().to_string()
-                };
-                let final_num = if input.trim().is_empty() {
-                    def.unwrap_or(0)
-                } else {
-                    input.trim().parse::<i64>().map_err(|e| anyhow::anyhow!("Failed to parse number: {}", e))?
-                };
-                vars.insert(spec.name.clone(), Value::Number(Number::from(final_num)));
-            }
-            VarKind::Choice(choices) => {
-                // Display dictionary-style mapping: "value": "label"
-                // Header: if name ends with "_code", show base name capitalized (language_code -> Language)
-                let display_name = if spec.name.ends_with("_code") {
-                    let base = spec.name.trim_end_matches("_code");
-                    let mut chars = base.chars();
-                    match chars.next() {
-                        Some(first) => format!("{}{}", first.to_uppercase(), chars.as_str()),
-                        None => spec.name.clone(),
-                    }
-                } else {
-                    spec.name.clone()
-                };
-                println!("{}:", display_name);
-
-                // Labels map is optional; fallback to echoing the value itself.
-                let labels = spec.choice_labels.clone().unwrap_or_default();
-                for c in choices.iter() {
-                    let label = labels.get(c).cloned().unwrap_or_else(|| c.clone());
-                    println!("  \"{}\": \"{}\"", c, label);
+                    eprintln!("Invalid value for {}: {}", spec.name, msg);
+                    continue;
                 }
-
-                // Determine default value from current vars or first choice
-                let default_val = if let Some(Value::String(d)) = vars.get(&spec.name) {
-                    if choices.contains(d) { d.clone() } else { choices.first().cloned().unwrap_or_default() }
-                } else { choices.first().cloned().unwrap_or_default() };
-
-                let input: String = if is_tty {
-                    Input::new()
-                        .with_prompt(format!("Enter value (default: {})", default_val))
-                        .allow_empty(true)
-                        .interact_text()?
-                } else {
-                    println!("Enter value (default: {})", default_val);
-                    let mut buf = String::new();
-                    io::stdin().read_line(&mut buf)?;
-                    buf.trim_end().to_string()
-                };
-                let picked = if input.trim().is_empty() {
-                    default_val
-                } else if choices.contains(&input.trim().to_string()) {
-                    input.trim().to_string()
-                } else {
-                    eprintln!("Invalid value, using default.");
-                    default_val
-                };
-                vars.insert(spec.name.clone(), Value::String(picked));
             }
+            answers.insert(spec.name.clone(), value.clone());
+            vars.insert(spec.name.clone(), value);
+            break;
+        }
+    }
+
+    // Run post_prompt hooks for cross-variable validation and final adjustments
+    let answers_json = serde_json::Value::Object(vars.iter().map(|(k,v)| (k.clone(), v.clone())).collect());
+    let post_prompt = run_post_prompt(root, &answers_json)?;
+    if !post_prompt.errors.is_empty() {
+        anyhow::bail!(format!("post_prompt validation failed:\n  {}", post_prompt.errors.join("\n  ")));
+    }
+    if let Some(updated) = post_prompt.updated_vars {
+        if let Some(obj) = updated.as_object() {
+            for (k, v) in obj.iter() { vars.insert(k.clone(), v.clone()); }
         }
     }
 
@@ -191,65 +140,108 @@ fn run(source: String, output: PathBuf) -> Result<()> {
     let staging_out = staging.path().join("out");
     std::fs::create_dir_all(&staging_out)?;
 
-    // Run pre_gen_project.lua in temp context, targeting staging output
-    let vars_json = serde_json::Value::Object(vars.iter().map(|(k,v)| (k.clone(), v.clone())).collect());
-    let pre = run_pre_gen(root, &vars_json, &staging_out)?;
-    // Ensure hook-created files are placed under the main project directory.
-    let proj_slug = sanitize_slug_python(vars.get("project_slug").and_then(|v| v.as_str()).unwrap_or("project"));
+    // Hook-created files are placed under the main project directory, which is also
+    // the working directory for shell hooks. That is the `project_slug` directory when
+    // one of the roots renders to it, and the output root otherwise.
+    let proj_slug = if manifest.renders_into_output_root() {
+        String::new()
+    } else {
+        let slug = manifest.slug.apply(vars.get("project_slug").and_then(|v| v.as_str()).unwrap_or("project"));
+        // `_slug: "none"` and custom patterns may leave characters unusable in a directory name
+        if !crate::util::is_safe_path_segment(&slug) {
+            anyhow::bail!(format!("Invalid 'project_slug' after normalization: '{}'", slug));
+        }
+        if roots_render_to(root, &manifest, &vars, &slug)? { slug } else { String::new() }
+    };
     let proj_root = staging_out.join(&proj_slug);
     std::fs::create_dir_all(&proj_root)?;
-    let proj_root_canon = proj_root.canonicalize()?;
-    for (p, content) in pre.created_files {
-        let p_str = p.to_string_lossy();
-        if !is_safe_rel_path(&p_str) {
-            anyhow::bail!(format!("Unsafe hook-created file path: {}", p_str));
-        }
-        let target = safe_resolve_under_canon(&proj_root_canon, &p)?;
-        if let Some(parent) = target.parent() { std::fs::create_dir_all(parent)?; }
-        std::fs::write(&target, content)?;
-    }
+
+    // Run pre_gen_project hooks in temp context, targeting staging output
+    let vars_json = serde_json::Value::Object(vars.iter().map(|(k,v)| (k.clone(), v.clone())).collect());
+    let pre = run_pre_gen(root, &vars_json, &staging_out, &proj_root)?;
+    write_hook_files(&proj_root, pre.created_files)?;
 
     println!("Rendering templates...");
 
-    let copy_filter: CopyFilter = manifest.compile_copy_filter()?;
-    renderer::render_all(root, &staging_out, &vars, &copy_filter)?;
+    let path_rules: PathRules = manifest.compile_path_rules()?;
+    let file_hooks = FileHooks::load(root)?;
+    let exclude_filter: CopyFilter = manifest.compile_exclude_filter(&vars)?;
+    let file_modes: FileModes = manifest.compile_file_modes()?;
+    let text_attributes = manifest.compile_text_attributes()?;
+    let fragment_dirs = manifest.compile_fragment_dirs()?;
+    let render_opts = RenderOptions {
+        path_rules: &path_rules,
+        exclude_filter: &exclude_filter,
+        file_modes: &file_modes,
+        text_attributes: &text_attributes,
+        fragment_dirs: &fragment_dirs,
+        template_suffixes: &manifest.template_suffixes,
+        file_hooks: &file_hooks,
+        roots: &manifest.roots,
+        jinja: &manifest.jinja,
+        slug: &manifest.slug,
+        partials_dir: manifest.partials_dir(),
+        jobs,
+        verbose,
+    };
+    let text_outputs = renderer::render_all(root, &staging_out, &vars, &render_opts)?;
 
-    // Run post_gen_project.lua (also targeting staging output)
+    // Run post_gen_project hooks (also targeting staging output)
     let vars_json2 = serde_json::Value::Object(vars.iter().map(|(k,v)| (k.clone(), v.clone())).collect());
-    let post = run_post_gen(root, &vars_json2, &staging_out)?;
-    // Post-gen files also go under the main project directory.
     let proj_root2 = staging_out.join(&proj_slug);
     std::fs::create_dir_all(&proj_root2)?;
-    let proj_root2_canon = proj_root2.canonicalize()?;
-    for (p, content) in post.created_files {
-        let p_str = p.to_string_lossy();
-        if !is_safe_rel_path(&p_str) {
-            anyhow::bail!(format!("Unsafe hook-created file path: {}", p_str));
-        }
-        let target = safe_resolve_under_canon(&proj_root2_canon, &p)?;
-        if let Some(parent) = target.parent() { std::fs::create_dir_all(parent)?; }
-        std::fs::write(&target, content)?;
+    let post = run_post_gen(root, &vars_json2, &staging_out, &proj_root2)?;
+    // Post-gen files also go under the main project directory.
+    write_hook_files(&proj_root2, post.created_files)?;
+    // Don't emit a project directory that neither templates nor hooks wrote into
+    if proj_root2 != staging_out && std::fs::read_dir(&proj_root2)?.next().is_none() {
+        std::fs::remove_dir(&proj_root2)?;
     }
 
+    // Format generated files in staging so the final output is already formatted
+    let formatters = manifest.compile_formatters()?;
+    formatters::run_formatters(&staging_out, &formatters, manifest.renders_into_output_root(), jobs)?;
+    // Line endings and encoding (`_text_attributes`) come last so formatters work on UTF-8
+    renderer::encode_outputs(&staging_out, &text_outputs)?;
+
     // Ensure final output root exists before secure resolution
     std::fs::create_dir_all(&output)?;
     let output_canon = output.canonicalize()?;
 
-    // Step c) copy processed files from staging to final output
-    for entry in walkdir::WalkDir::new(&staging_out).into_iter().filter_map(|e| e.ok()) {
+    // Step c) move processed files from staging to final output (copying when the
+    // output lives on another filesystem). The walk is collected first so that moving
+    // files never races the directory iterator.
+    let entries: Vec<walkdir::DirEntry> = walkdir::WalkDir::new(&staging_out)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .collect();
+    for entry in entries {
         let path = entry.path();
         let rel = path
             .strip_prefix(&staging_out)
             .with_context(|| format!("Failed to compute relative path from staging: {}", path.display()))?;
         let target = safe_resolve_under_canon(&output_canon, rel)?;
-        if path.is_dir() {
+        if entry.path_is_symlink() {
+            if let Some(parent) = target.parent() { std::fs::create_dir_all(parent)?; }
+            crate::util::copy_symlink(path, &target)?;
+        } else if path.is_dir() {
             std::fs::create_dir_all(&target)?;
         } else {
             if let Some(parent) = target.parent() { std::fs::create_dir_all(parent)?; }
-            std::fs::copy(path, &target)?;
+            crate::util::move_or_copy(path, &target)?;
         }
     }
 
+    // Run post_copy hooks against the real output (e.g. `git init`)
+    let out_proj_root = output.join(&proj_slug);
+    let post_copy_cwd = if out_proj_root.is_dir() { out_proj_root.clone() } else { output.clone() };
+    let post_copy = run_post_copy(root, &vars_json2, &output, &post_copy_cwd)?;
+    if !post_copy.created_files.is_empty() {
+        std::fs::create_dir_all(&out_proj_root)?;
+        write_hook_files(&out_proj_root, post_copy.created_files)?;
+    }
+
     // Step d) delete temp dirs by dropping guards (TempDir cleans up on drop)
     drop(staging);
     drop(temp_root_guard);
@@ -259,10 +251,153 @@ fn run(source: String, output: PathBuf) -> Result<()> {
     Ok(())
 }
 
-fn parse_args() -> Result<(String, PathBuf)> {
+// Whether one of the template roots renders to the directory name `slug`.
+fn roots_render_to(root: &Path, manifest: &Manifest, vars: &BTreeMap<String, serde_json::Value>, slug: &str) -> Result<bool> {
+    let env = manifest.jinja.environment()?;
+    let mut ctx = vars.clone();
+    ctx.insert("project_slug".to_string(), serde_json::Value::String(slug.to_string()));
+    for r in renderer::resolve_roots(root, &manifest.roots, &manifest.jinja)? {
+        let name = env.render_str(&r, &ctx)
+            .with_context(|| format!("Failed to render root directory name: {}", r))?;
+        if name == slug { return Ok(true); }
+    }
+    Ok(false)
+}
+
+// Write files returned by a hook under `project_root`, rejecting unsafe paths.
+fn write_hook_files(project_root: &Path, files: Vec<(PathBuf, String)>) -> Result<()> {
+    let root_canon = project_root.canonicalize()?;
+    for (p, content) in files {
+        let p_str = p.to_string_lossy();
+        if !is_safe_rel_path(&p_str) {
+            anyhow::bail!(format!("Unsafe hook-created file path: {}", p_str));
+        }
+        let target = safe_resolve_under_canon(&root_canon, &p)?;
+        if let Some(parent) = target.parent() { std::fs::create_dir_all(parent)?; }
+        std::fs::write(&target, content)?;
+    }
+    Ok(())
+}
+
+// Prompt for a single variable, using the current value in `vars` as default.
+// `eof` is set once stdin is exhausted in non-TTY mode.
+fn prompt_var(spec: &VarDef, vars: &BTreeMap<String, Value>, is_tty: bool, eof: &mut bool) -> Result<Value> {
+    match &spec.kind {
+        VarKind::String => {
+            let def = vars.get(&spec.name).and_then(|v| v.as_str()).map(|s| s.to_string());
+            let prompt = format!("Enter {}:", spec.name);
+            let input: String = if is_tty {
+                if let Some(d) = def.clone() {
+                    Input::new().with_prompt(format!("{} (default: {})", prompt, d)).allow_empty(true).interact_text()?
+                } else {
+                    Input::new().with_prompt(&prompt).interact_text()?
+                }
+            } else {
+                println!("{}{}", prompt, def.as_ref().map(|d| format!(" (default: {})", d)).unwrap_or_default());
+                let buf = read_stdin_line(eof)?;
+                buf.trim_end().to_string()
+            };
+            let final_value = if input.is_empty() { def.unwrap_or_default() } else { input };
+            Ok(Value::String(final_value))
+        }
+        VarKind::Bool => {
+            let def = vars.get(&spec.name).and_then(|v| v.as_bool()).unwrap_or(false);
+            let val = if is_tty {
+                Confirm::new()
+                    .with_prompt(format!("{}?", spec.name))
+                    .default(def)
+                    .interact()?
+            } else {
+                println!("{}? (y/n, default: {})", spec.name, if def { "y" } else { "n" });
+                let buf = read_stdin_line(eof)?;
+                let s = buf.trim().to_ascii_lowercase();
+                if s.is_empty() { def } else { s.starts_with('y') }
+            };
+            Ok(Value::Bool(val))
+        }
+        VarKind::Number => {
+            let def = vars.get(&spec.name).and_then(|v| v.as_i64());
+            let prompt = format!("Enter number for {}:", spec.name);
+            let input: String = if is_tty {
+                if let Some(d) = def {
+                    Input::new().with_prompt(format!("{} (default: {})", prompt, d)).allow_empty(true).interact_text()?
+                } else {
+                    Input::new().with_prompt(&prompt).interact_text()?
+                }
+            } else {
+                println!("{}{}", prompt, def.map(|d| format!(" (default: {})", d)).unwrap_or_default());
+                let buf = read_stdin_line(eof)?;
+                buf.trim_end().to_string()
+            };
+            let final_num = if input.trim().is_empty() {
+                def.unwrap_or(0)
+            } else {
+                input.trim().parse::<i64>().map_err(|e| anyhow::anyhow!("Failed to parse number: {}", e))?
+            };
+            Ok(Value::Number(Number::from(final_num)))
+        }
+        VarKind::Choice(choices) => {
+            // Display dictionary-style mapping: "value": "label"
+            // Header: if name ends with "_code", show base name capitalized (language_code -> Language)
+            let display_name = if spec.name.ends_with("_code") {
+                let base = spec.name.trim_end_matches("_code");
+                let mut chars = base.chars();
+                match chars.next() {
+                    Some(first) => format!("{}{}", first.to_uppercase(), chars.as_str()),
+                    None => spec.name.clone(),
+                }
+            } else {
+                spec.name.clone()
+            };
+            println!("{}:", display_name);
+
+            // Labels map is optional; fallback to echoing the value itself.
+            let labels = spec.choice_labels.clone().unwrap_or_default();
+            for c in choices.iter() {
+                let label = labels.get(c).cloned().unwrap_or_else(|| c.clone());
+                println!("  \"{}\": \"{}\"", c, label);
+            }
+
+            // Determine default value from current vars or first choice
+            let default_val = if let Some(Value::String(d)) = vars.get(&spec.name) {
+                if choices.contains(d) { d.clone() } else { choices.first().cloned().unwrap_or_default() }
+            } else { choices.first().cloned().unwrap_or_default() };
+
+            let input: String = if is_tty {
+                Input::new()
+                    .with_prompt(format!("Enter value (default: {})", default_val))
+                    .allow_empty(true)
+                    .interact_text()?
+            } else {
+                println!("Enter value (default: {})", default_val);
+                let buf = read_stdin_line(eof)?;
+                buf.trim_end().to_string()
+            };
+            let picked = if input.trim().is_empty() {
+                default_val
+            } else if choices.contains(&input.trim().to_string()) {
+                input.trim().to_string()
+            } else {
+                eprintln!("Invalid value, using default.");
+                default_val
+            };
+            Ok(Value::String(picked))
+        }
+    }
+}
+
+fn read_stdin_line(eof: &mut bool) -> Result<String> {
+    let mut buf = String::new();
+    if io::stdin().read_line(&mut buf)? == 0 { *eof = true; }
+    Ok(buf)
+}
+
+fn parse_args() -> Result<Args> {
     let mut args = env::args().skip(1);
     let mut source: Option<String> = None;
     let mut output = PathBuf::from(".");
+    let mut verbose = false;
+    let mut jobs: Option<usize> = None;
 
     // Default: first argument is SOURCE; optionally support "--output <dir>"
     while let Some(arg) = args.next() {
@@ -275,10 +410,20 @@ fn parse_args() -> Result<(String, PathBuf)> {
                 if let Some(val) = args.next() { output = PathBuf::from(val); }
                 else { return Err(anyhow::anyhow!("Missing value for --output")); }
             }
+            "-v" | "--verbose" => { verbose = true; }
+            "-j" | "--jobs" => {
+                let val = args.next().ok_or_else(|| anyhow::anyhow!("Missing value for --jobs"))?;
+                match val.parse::<usize>() {
+                    Ok(n) if n > 0 => jobs = Some(n),
+                    _ => return Err(anyhow::anyhow!("Invalid value for --jobs: {}", val)),
+                }
+            }
             _ => { if source.is_none() { source = Some(arg); } }
         }
     }
 
     let source = source.ok_or_else(|| anyhow::anyhow!("Missing SOURCE argument"))?;
-    Ok((source, output))
+    // Default to one worker per available CPU
+    let jobs = jobs.unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1));
+    Ok(Args { source, output, verbose, jobs })
 }
\ No newline at end of file
@@ -1,8 +1,9 @@
 use anyhow::{Context, Result};
-// Custom minimal glob matcher to avoid heavy regex dependencies
+use crate::glob::Glob;
 use serde_json::Value;
 use std::collections::BTreeMap;
 use minijinja::Environment;
+use minijinja::syntax::SyntaxConfig;
 use std::{fs, path::Path};
 
 #[derive(Debug, Clone)]
@@ -21,12 +22,133 @@ pub struct VarDef {
     // Optional labels for choices when the variable is defined as a dictionary.
     // Keys are the actual values; values are human-friendly labels.
     pub choice_labels: Option<BTreeMap<String, String>>, // None for non-choice vars
+    // Hidden variables keep their default value but are never prompted.
+    pub hidden: bool,
+}
+
+// Question-list modifications requested by the pre_prompt hook.
+// - `hide`: variables that keep their defaults but are not prompted
+// - `add`: new (or replacement) variable definitions, appended in order
+// - `order`: variable names to prompt first, in the given order
+#[derive(Debug, Clone, Default)]
+pub struct QuestionEdits {
+    pub hide: Vec<String>,
+    pub add: Vec<VarDef>,
+    pub order: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct Manifest {
     pub variables: Vec<VarDef>,
     pub copy_without_render: Vec<String>,
+    // Template root entries to emit, e.g. ["{{ project_slug }}", "{{ project_slug }}-docs"].
+    // "." renders the template root itself into the output root. Empty = auto-detect.
+    pub roots: Vec<String>,
+    // `_exclude` patterns with an optional Jinja condition; None means always excluded.
+    pub exclude: Vec<(String, Option<String>)>,
+    // `_file_modes`: ordered (pattern, octal permission string) rules, e.g. ("scripts/*.sh", "755")
+    pub file_modes: Vec<(String, String)>,
+    // `_path_modes`: ordered (pattern, mode) rules, see PathMode
+    pub path_modes: Vec<(String, PathMode)>,
+    // `_jinja`: delimiter and whitespace settings for every Jinja environment
+    pub jinja: JinjaOptions,
+    // `_partials`: template-root directory with shared templates for include/import/extends
+    pub partials: Option<String>,
+    // `_slug`: how `project_slug` is normalized, see SlugPolicy
+    pub slug: SlugPolicy,
+    // `_text_attributes`: gitattributes-style lines, e.g. "*.bat eol=crlf"
+    pub text_attributes: Vec<String>,
+    // `_formatters`: ordered (pattern, command argv) rules run on generated files
+    pub formatters: Vec<(String, Vec<String>)>,
+    // `_fragments`: patterns for `X.d` directories whose files are merged into `X`
+    pub fragments: Vec<String>,
+    // `_template_suffixes`: suffixes stripped from rendered file names, e.g. ".j2"
+    pub template_suffixes: Vec<String>,
+}
+
+// Template syntax options from `_jinja` in copilot.json, e.g.
+// { "variable_start": "[[", "variable_end": "]]", "trim_blocks": true }
+// Unset delimiters keep the MiniJinja defaults.
+#[derive(Debug, Clone, Default)]
+pub struct JinjaOptions {
+    pub block_start: Option<String>,
+    pub block_end: Option<String>,
+    pub variable_start: Option<String>,
+    pub variable_end: Option<String>,
+    pub comment_start: Option<String>,
+    pub comment_end: Option<String>,
+    pub trim_blocks: bool,
+    pub lstrip_blocks: bool,
+    pub keep_trailing_newline: bool,
+}
+
+impl JinjaOptions {
+    fn parse(v: &Value) -> Result<JinjaOptions> {
+        let obj = v.as_object().ok_or_else(|| anyhow::anyhow!("'_jinja' in copilot.json must be an object"))?;
+        let mut opts = JinjaOptions::default();
+        for (k, val) in obj.iter() {
+            let text = || -> Result<Option<String>> {
+                let s = val.as_str().ok_or_else(|| anyhow::anyhow!(format!("'_jinja.{}' must be a string", k)))?;
+                if s.is_empty() { anyhow::bail!(format!("'_jinja.{}' must not be empty", k)); }
+                Ok(Some(s.to_string()))
+            };
+            let flag = || -> Result<bool> {
+                val.as_bool().ok_or_else(|| anyhow::anyhow!(format!("'_jinja.{}' must be a boolean", k)))
+            };
+            match k.as_str() {
+                "block_start" => opts.block_start = text()?,
+                "block_end" => opts.block_end = text()?,
+                "variable_start" => opts.variable_start = text()?,
+                "variable_end" => opts.variable_end = text()?,
+                "comment_start" => opts.comment_start = text()?,
+                "comment_end" => opts.comment_end = text()?,
+                "trim_blocks" => opts.trim_blocks = flag()?,
+                "lstrip_blocks" => opts.lstrip_blocks = flag()?,
+                "keep_trailing_newline" => opts.keep_trailing_newline = flag()?,
+                other => anyhow::bail!(format!("Unknown option '_jinja.{}'", other)),
+            }
+        }
+        Ok(opts)
+    }
+
+    pub fn variable_delimiters(&self) -> (&str, &str) {
+        (self.variable_start.as_deref().unwrap_or("{{"), self.variable_end.as_deref().unwrap_or("}}"))
+    }
+
+    // Build a MiniJinja environment configured with these options.
+    pub fn environment(&self) -> Result<Environment<'static>> {
+        let mut env = Environment::new();
+        let (var_start, var_end) = self.variable_delimiters();
+        let syntax = SyntaxConfig::builder()
+            .block_delimiters(
+                self.block_start.clone().unwrap_or_else(|| "{%".to_string()),
+                self.block_end.clone().unwrap_or_else(|| "%}".to_string()),
+            )
+            .variable_delimiters(var_start.to_string(), var_end.to_string())
+            .comment_delimiters(
+                self.comment_start.clone().unwrap_or_else(|| "{#".to_string()),
+                self.comment_end.clone().unwrap_or_else(|| "#}".to_string()),
+            )
+            .build()
+            .with_context(|| "Invalid '_jinja' delimiters in copilot.json")?;
+        env.set_syntax(syntax);
+        env.set_trim_blocks(self.trim_blocks);
+        env.set_lstrip_blocks(self.lstrip_blocks);
+        env.set_keep_trailing_newline(self.keep_trailing_newline);
+        // `{{ title | slugify }}` -> "cafe-ueber"; `slugify("_")` picks another separator
+        env.add_filter("slugify", |value: String, sep: Option<String>| -> Result<String, minijinja::Error> {
+            let sep = sep.unwrap_or_else(|| "-".to_string());
+            let mut chars = sep.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(crate::util::slugify(&value, c)),
+                _ => Err(minijinja::Error::new(
+                    minijinja::ErrorKind::InvalidOperation,
+                    format!("slugify separator must be a single character, got {:?}", sep),
+                )),
+            }
+        });
+        Ok(env)
+    }
 }
 
 pub fn load_manifest(dir: &Path) -> Result<Manifest> {
@@ -53,6 +175,152 @@ pub fn load_manifest(dir: &Path) -> Result<Manifest> {
         manifest.copy_without_render = paths;
     }
 
+    // `_exclude` accepts an ordered array whose entries are patterns (always excluded) or
+    // `{ "pattern": ..., "if": ... }` rules with a Jinja condition, or an object mapping
+    // patterns to conditions, e.g. { "docker/**": "not use_docker" }. Object keys lose
+    // their order, so `!` re-inclusion is only allowed in the array form.
+    match obj.get("_exclude") {
+        None => {}
+        Some(Value::Array(arr)) => {
+            for v in arr {
+                match v {
+                    Value::String(p) => manifest.exclude.push((p.clone(), None)),
+                    Value::Object(rule) => {
+                        let p = rule.get("pattern").and_then(|p| p.as_str())
+                            .ok_or_else(|| anyhow::anyhow!("'_exclude' rules need a string 'pattern'"))?;
+                        let cond = match rule.get("if") {
+                            None => Some(None),
+                            Some(cond) => exclude_condition(p, cond)?,
+                        };
+                        if let Some(cond) = cond {
+                            manifest.exclude.push((p.to_string(), cond));
+                        }
+                    }
+                    _ => anyhow::bail!("'_exclude' entries must be patterns or rules with 'pattern' and 'if'"),
+                }
+            }
+        }
+        Some(Value::Object(map)) => {
+            for (p, cond) in map.iter() {
+                if p.starts_with('!') {
+                    anyhow::bail!(format!(
+                        "Negated pattern '{}' in the '_exclude' object is applied out of order; use the array form with {{ \"pattern\", \"if\" }} rules",
+                        p
+                    ));
+                }
+                if let Some(cond) = exclude_condition(p, cond)? {
+                    manifest.exclude.push((p.clone(), cond));
+                }
+            }
+        }
+        Some(_) => anyhow::bail!("'_exclude' in copilot.json must be an array or an object"),
+    }
+
+    // `_file_modes` is an ordered array; the last matching rule wins:
+    // [{ "pattern": "**/*.sh", "mode": "755" }, { "pattern": "scripts/private/*.sh", "mode": "700" }]
+    if let Some(v) = obj.get("_file_modes") {
+        let arr = v.as_array().ok_or_else(|| anyhow::anyhow!("'_file_modes' in copilot.json must be an array of rules with 'pattern' and 'mode'"))?;
+        for rule in arr {
+            let pattern = rule.get("pattern").and_then(|p| p.as_str())
+                .ok_or_else(|| anyhow::anyhow!("'_file_modes' entries need a string 'pattern'"))?;
+            let mode = rule.get("mode").and_then(|m| m.as_str())
+                .ok_or_else(|| anyhow::anyhow!(format!("'_file_modes' mode for '{}' must be an octal string such as \"755\"", pattern)))?;
+            manifest.file_modes.push((pattern.to_string(), mode.to_string()));
+        }
+    }
+
+    // `_text_attributes` is ordered like a .gitattributes file: ["* eol=lf", "*.bat eol=crlf"]
+    if let Some(v) = obj.get("_text_attributes") {
+        let arr = v.as_array().ok_or_else(|| anyhow::anyhow!("'_text_attributes' in copilot.json must be an array of strings"))?;
+        for line in arr {
+            let line = line.as_str().ok_or_else(|| anyhow::anyhow!("'_text_attributes' in copilot.json must be an array of strings"))?;
+            manifest.text_attributes.push(line.to_string());
+        }
+    }
+
+    // `_formatters` run on generated files in order:
+    // [{ "pattern": "*.rs", "command": "rustfmt --edition 2021" }, { "pattern": "*.py", "command": ["black", "-q"] }]
+    if let Some(v) = obj.get("_formatters") {
+        let arr = v.as_array().ok_or_else(|| anyhow::anyhow!("'_formatters' in copilot.json must be an array"))?;
+        for rule in arr {
+            let pattern = rule.get("pattern").and_then(|p| p.as_str())
+                .ok_or_else(|| anyhow::anyhow!("'_formatters' entries need a string 'pattern'"))?;
+            let command: Vec<String> = match rule.get("command") {
+                Some(Value::String(c)) => c.split_whitespace().map(|a| a.to_string()).collect(),
+                Some(Value::Array(args)) => args.iter()
+                    .map(|a| a.as_str().map(|a| a.to_string()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| anyhow::anyhow!(format!("'_formatters' command for '{}' must contain only strings", pattern)))?,
+                _ => anyhow::bail!(format!("'_formatters' entry '{}' needs a 'command' string or array", pattern)),
+            };
+            if command.is_empty() {
+                anyhow::bail!(format!("'_formatters' entry '{}' has an empty command", pattern));
+            }
+            manifest.formatters.push((pattern.to_string(), command));
+        }
+        // Fail early on invalid patterns
+        manifest.compile_formatters()?;
+    }
+
+    if let Some(v) = obj.get("_fragments") {
+        manifest.fragments = string_list(v, "_fragments")?;
+        // Fail early on invalid patterns
+        manifest.compile_fragment_dirs()?;
+    }
+
+    if let Some(v) = obj.get("_template_suffixes") {
+        manifest.template_suffixes = string_list(v, "_template_suffixes")?;
+        if manifest.template_suffixes.iter().any(|s| s.is_empty() || s.contains('/') || s.contains('\\')) {
+            anyhow::bail!("'_template_suffixes' entries must be non-empty file name suffixes such as \".j2\"");
+        }
+    }
+
+    // `_path_modes` is an ordered array so that later rules can override earlier ones:
+    // [{ "pattern": "vendor/**", "mode": "raw" }, { "pattern": "vendor/README.md", "mode": "render" }]
+    if let Some(v) = obj.get("_path_modes") {
+        let arr = v.as_array().ok_or_else(|| anyhow::anyhow!("'_path_modes' in copilot.json must be an array"))?;
+        for rule in arr {
+            let pattern = rule.get("pattern").and_then(|p| p.as_str())
+                .ok_or_else(|| anyhow::anyhow!("'_path_modes' entries need a string 'pattern'"))?;
+            let mode_str = rule.get("mode").and_then(|m| m.as_str())
+                .ok_or_else(|| anyhow::anyhow!(format!("'_path_modes' entry '{}' needs a string 'mode'", pattern)))?;
+            let mode = PathMode::parse(mode_str)
+                .ok_or_else(|| anyhow::anyhow!(format!("Unknown mode '{}' for '{}' in _path_modes", mode_str, pattern)))?;
+            manifest.path_modes.push((pattern.to_string(), mode));
+        }
+    }
+
+    if let Some(v) = obj.get("_jinja") {
+        manifest.jinja = JinjaOptions::parse(v)?;
+        // Fail early on conflicting delimiters
+        manifest.jinja.environment()?;
+    }
+
+    if let Some(v) = obj.get("_partials") {
+        let dir = v.as_str().ok_or_else(|| anyhow::anyhow!("'_partials' in copilot.json must be a string"))?;
+        let dir = dir.trim().trim_end_matches('/');
+        if !crate::util::is_safe_rel_path(dir) {
+            anyhow::bail!(format!("Invalid '_partials' directory: {}", dir));
+        }
+        manifest.partials = Some(dir.to_string());
+    }
+
+    if let Some(v) = obj.get("_slug") {
+        manifest.slug = SlugPolicy::parse(v)?;
+    }
+
+    if let Some(v) = obj.get("_roots") {
+        let arr = v.as_array().ok_or_else(|| anyhow::anyhow!("'_roots' in copilot.json must be an array of strings"))?;
+        for r in arr {
+            let r = r.as_str().ok_or_else(|| anyhow::anyhow!("'_roots' in copilot.json must be an array of strings"))?;
+            let r = r.trim().trim_end_matches('/');
+            if r.is_empty() || (r != "." && (r.contains('/') || r.contains('\\') || r == "..")) {
+                anyhow::bail!(format!("Invalid entry in '_roots': {}", r));
+            }
+            manifest.roots.push(r.to_string());
+        }
+    }
+
     // `_extensions` is intentionally ignored to keep the manifest minimal.
     // Users can implement custom logic via hooks instead.
 
@@ -64,9 +332,9 @@ pub fn load_manifest(dir: &Path) -> Result<Manifest> {
     for (k, v) in obj.iter() {
         if k.starts_with('_') { continue; }
         let def = match v {
-            Value::String(_) => VarDef { name: k.clone(), kind: VarKind::String, default: Some(v.clone()), choice_labels: None },
-            Value::Bool(_) => VarDef { name: k.clone(), kind: VarKind::Bool, default: Some(v.clone()), choice_labels: None },
-            Value::Number(_) => VarDef { name: k.clone(), kind: VarKind::Number, default: Some(v.clone()), choice_labels: None },
+            Value::String(_) => VarDef { name: k.clone(), kind: VarKind::String, default: Some(v.clone()), choice_labels: None, hidden: false },
+            Value::Bool(_) => VarDef { name: k.clone(), kind: VarKind::Bool, default: Some(v.clone()), choice_labels: None, hidden: false },
+            Value::Number(_) => VarDef { name: k.clone(), kind: VarKind::Number, default: Some(v.clone()), choice_labels: None, hidden: false },
             Value::Array(arr) => {
                 // Only support an array of string choices
                 let choices: Vec<String> = arr.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect();
@@ -74,7 +342,7 @@ pub fn load_manifest(dir: &Path) -> Result<Manifest> {
                     continue; // skip unsupported types
                 }
                 let default = choices.first().map(|s| Value::String(s.clone()));
-                VarDef { name: k.clone(), kind: VarKind::Choice(choices), default, choice_labels: None }
+                VarDef { name: k.clone(), kind: VarKind::Choice(choices), default, choice_labels: None, hidden: false }
             }
             Value::Object(map) => {
                 // Dictionary-format choices support (avoids __prompts__ mechanism):
@@ -91,7 +359,7 @@ pub fn load_manifest(dir: &Path) -> Result<Manifest> {
                 }
                 if keys.is_empty() { continue; }
                 let default = keys.first().map(|s| Value::String(s.clone()));
-                VarDef { name: k.clone(), kind: VarKind::Choice(keys), default, choice_labels: Some(labels) }
+                VarDef { name: k.clone(), kind: VarKind::Choice(keys), default, choice_labels: Some(labels), hidden: false }
             }
             _ => continue,
         };
@@ -101,22 +369,117 @@ pub fn load_manifest(dir: &Path) -> Result<Manifest> {
 }
 
 impl Manifest {
-    // Compile copy filter using minimal glob support.
-    // Supported:
-    // - Segment wildcard '*'
-    // - Recursive wildcard '**' across directory boundaries
-    // Invalid characters like '[' or ']' will produce an error to match tests.
-    pub fn compile_copy_filter(&self) -> Result<CopyFilter> {
-        let mut pats = Vec::new();
+    // Directory holding shared partials, relative to the template root.
+    pub fn partials_dir(&self) -> &str {
+        self.partials.as_deref().unwrap_or("_partials")
+    }
+
+    // True when the template renders straight into the output root ("." in `_roots`),
+    // in which case hooks target the output root instead of the project directory.
+    pub fn renders_into_output_root(&self) -> bool {
+        self.roots.iter().any(|r| r == ".")
+    }
+
+    // Apply question edits from the pre_prompt hook.
+    // Added definitions replace existing ones with the same name; unknown names
+    // in `hide` and `order` are ignored. Variables not listed in `order` keep
+    // their relative position after the ordered ones.
+    pub fn apply_question_edits(&mut self, edits: &QuestionEdits) {
+        for def in &edits.add {
+            match self.variables.iter_mut().find(|v| v.name == def.name) {
+                Some(existing) => *existing = def.clone(),
+                None => self.variables.push(def.clone()),
+            }
+        }
+        for name in &edits.hide {
+            if let Some(def) = self.variables.iter_mut().find(|v| &v.name == name) {
+                def.hidden = true;
+            }
+        }
+        if let Some(order) = &edits.order {
+            let rank = |name: &str| order.iter().position(|o| o == name).unwrap_or(order.len());
+            // Stable sort keeps manifest order for unlisted variables
+            self.variables.sort_by_key(|v| rank(&v.name));
+        }
+    }
+
+    // Compile path rules: `_copy_without_render` patterns first (name rendered, content raw),
+    // then `_path_modes` in order. See glob.rs for the supported syntax. A negated
+    // `_copy_without_render` pattern ("!tests/fixtures/*.j2") switches matching paths
+    // back to normal rendering, giving gitignore-like last-match-wins behavior.
+    pub fn compile_path_rules(&self) -> Result<PathRules> {
+        let mut rules = Vec::new();
         for pat in &self.copy_without_render {
-            let p = pat.trim();
-            if p.is_empty() { anyhow::bail!("Invalid empty pattern in _copy_without_render"); }
-            if p.contains('[') || p.contains(']') {
-                anyhow::bail!(format!("Invalid glob pattern: {}", pat));
+            let glob = Glob::parse("_copy_without_render", pat)?;
+            let mode = if glob.negated { PathMode::Render } else { PathMode::RenderName };
+            rules.push((glob, mode));
+        }
+        for (pat, mode) in &self.path_modes {
+            let glob = Glob::parse("_path_modes", pat)?;
+            if glob.negated {
+                anyhow::bail!(format!("Negated patterns are not supported in _path_modes: {}", pat));
+            }
+            rules.push((glob, *mode));
+        }
+        Ok(PathRules { rules })
+    }
+
+    pub fn compile_file_modes(&self) -> Result<FileModes> {
+        let mut rules = Vec::new();
+        for (pat, mode) in &self.file_modes {
+            let parsed = u32::from_str_radix(mode.trim_start_matches("0o"), 8)
+                .ok()
+                .filter(|m| *m <= 0o7777)
+                .ok_or_else(|| anyhow::anyhow!(format!("Invalid mode '{}' for '{}' in _file_modes", mode, pat)))?;
+            let glob = Glob::parse("_file_modes", pat)?;
+            if glob.negated {
+                anyhow::bail!(format!("Negative patterns are not allowed in '_file_modes': {}", pat));
             }
-            pats.push(p.replace('\\', "/"));
+            rules.push((glob, parsed));
+        }
+        Ok(FileModes { rules })
+    }
+
+    // Directories (relative to each root) whose files are fragments of one output file.
+    pub fn compile_fragment_dirs(&self) -> Result<CopyFilter> {
+        compile_patterns("_fragments", &self.fragments)
+    }
+
+    pub fn compile_formatters(&self) -> Result<Formatters> {
+        let mut rules = Vec::new();
+        for (pattern, command) in &self.formatters {
+            rules.push((attribute_glob("_formatters", pattern)?, command.clone()));
         }
-        Ok(CopyFilter { patterns: pats })
+        Ok(Formatters { rules })
+    }
+
+    pub fn compile_text_attributes(&self) -> Result<TextAttributes> {
+        let mut rules = Vec::new();
+        for line in &self.text_attributes {
+            rules.push(TextAttributeRule::parse(line)?);
+        }
+        Ok(TextAttributes { rules })
+    }
+
+    // Compile the `_exclude` patterns whose condition evaluates to true for `vars`.
+    // Conditions are Jinja expressions such as `not use_docker` or `ci == "none"`.
+    pub fn compile_exclude_filter(&self, vars: &BTreeMap<String, Value>) -> Result<CopyFilter> {
+        let env = self.jinja.environment()?;
+        let mut active = Vec::new();
+        for (pat, cond) in &self.exclude {
+            let excluded = match cond {
+                None => true,
+                Some(c) => {
+                    let expr = env.compile_expression(c)
+                        .with_context(|| format!("Invalid _exclude condition for '{}': {}", pat, c))?;
+                    expr.eval(vars)
+                        .with_context(|| format!("Failed to evaluate _exclude condition for '{}': {}", pat, c))?
+                        .is_true()
+                }
+            };
+            if excluded { active.push(pat.clone()); }
+        }
+        compile_patterns("_exclude", &active)
     }
 
     // Evaluate variable default values using Jinja syntax with dependency resolution.
@@ -125,7 +488,7 @@ impl Manifest {
     // - Performs multiple passes until values stabilize or max iteration threshold is reached
     // - On render errors, keeps original default to preserve backward compatibility
     pub fn evaluate_defaults(&self, initial: &BTreeMap<String, Value>) -> Result<BTreeMap<String, Value>> {
-        let env = Environment::new();
+        let env = self.jinja.environment()?;
         let mut vars = initial.clone();
         let max_passes = self.variables.len().max(1) * 2;
         for _ in 0..max_passes {
@@ -162,57 +525,408 @@ impl Manifest {
     }
 }
 
+// An `_exclude` condition: a Jinja expression, `true` (always) or `false` (never, None).
+fn exclude_condition(pattern: &str, cond: &Value) -> Result<Option<Option<String>>> {
+    match cond {
+        Value::String(c) => Ok(Some(Some(c.clone()))),
+        Value::Bool(true) => Ok(Some(None)),
+        Value::Bool(false) => Ok(None),
+        _ => anyhow::bail!(format!("'_exclude' condition for '{}' must be a string or boolean", pattern)),
+    }
+}
+
+fn string_list(v: &Value, field: &str) -> Result<Vec<String>> {
+    let arr = v.as_array().ok_or_else(|| anyhow::anyhow!(format!("'{}' in copilot.json must be an array of strings", field)))?;
+    arr.iter()
+        .map(|s| s.as_str().map(|s| s.to_string())
+            .ok_or_else(|| anyhow::anyhow!(format!("'{}' in copilot.json must be an array of strings", field))))
+        .collect()
+}
+
+fn compile_patterns(field: &str, patterns: &[String]) -> Result<CopyFilter> {
+    let mut pats = Vec::new();
+    for pat in patterns {
+        pats.push(Glob::parse(field, pat)?);
+    }
+    Ok(CopyFilter { patterns: pats })
+}
+
+// Ordered glob set with gitignore-like semantics: the last matching pattern
+// decides, and a '!' pattern un-matches paths matched by earlier ones.
 #[derive(Debug, Clone)]
 pub struct CopyFilter {
-    patterns: Vec<String>,
+    patterns: Vec<Glob>,
 }
 
 impl CopyFilter {
     pub fn is_match(&self, rel: &str) -> bool {
         let text = rel.replace('\\', "/");
-        for pat in &self.patterns {
-            if pattern_matches(pat, &text) { return true; }
+        let mut matched = false;
+        for glob in &self.patterns {
+            if glob.matches(&text) { matched = !glob.negated; }
         }
-        false
+        matched
     }
 }
 
-fn segment_matches(pat: &str, s: &str) -> bool {
-    if !pat.contains('*') { return pat == s; }
-    // Simple wildcard matcher: '*' matches any sequence within segment
-    let mut si = 0usize;
-    let mut first = true;
-    for token in pat.split('*') {
-        if token.is_empty() { if first { /* leading '*' */ } else { /* consecutive '*' */ } }
-        else if first && !pat.starts_with('*') {
-            if !s[si..].starts_with(token) { return false; }
-            si += token.len();
-        } else {
-            // find token anywhere after si
-            if let Some(pos_rel) = s[si..].find(token) {
-                si += pos_rel + token.len();
-            } else { return false; }
+// How a template path is turned into output:
+// - Render: render both the path name and the content (default)
+// - RenderName: render the name, copy content raw (`_copy_without_render`)
+// - RenderContent: keep the name as-is, render the content
+// - Raw: copy the path and content untouched
+// - Exclude: do not generate the path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMode {
+    Render,
+    RenderName,
+    RenderContent,
+    Raw,
+    Exclude,
+}
+
+impl PathMode {
+    pub fn parse(s: &str) -> Option<PathMode> {
+        match s {
+            "render" => Some(PathMode::Render),
+            "render_name" => Some(PathMode::RenderName),
+            "render_content" => Some(PathMode::RenderContent),
+            "raw" => Some(PathMode::Raw),
+            "exclude" => Some(PathMode::Exclude),
+            _ => None,
         }
-        first = false;
     }
-    if !pat.ends_with('*') { si == s.len() } else { true }
+
+    pub fn renders_name(self) -> bool {
+        matches!(self, PathMode::Render | PathMode::RenderName)
+    }
+
+    pub fn renders_content(self) -> bool {
+        matches!(self, PathMode::Render | PathMode::RenderContent)
+    }
+}
+
+// Normalization applied to `project_slug`, chosen with `_slug` in copilot.json:
+// "python" (default), "kebab", "rust-crate", "npm", "go-module", "none", or a custom
+// rule { "pattern": "<regex>", "replacement": "-", "lowercase": true, "transliterate": true }
+// where every match of `pattern` is replaced and leading/trailing replacements are trimmed.
+#[derive(Debug, Clone, Default)]
+pub enum SlugPolicy {
+    #[default]
+    Python,
+    Kebab,
+    RustCrate,
+    Npm,
+    GoModule,
+    None,
+    Custom { pattern: regex::Regex, replacement: String, lowercase: bool, transliterate: bool },
 }
 
-fn pattern_matches(pat: &str, path: &str) -> bool {
-    let psegs: Vec<&str> = pat.split('/').collect();
-    let ssegs: Vec<&str> = path.split('/').collect();
-    fn rec(p: &[&str], s: &[&str]) -> bool {
-        if p.is_empty() { return s.is_empty(); }
-        if p[0] == "**" {
-            if p.len() == 1 { return true; } // matches the rest
-            for skip in 0..=s.len() {
-                if rec(&p[1..], &s[skip..]) { return true; }
+impl SlugPolicy {
+    pub fn parse(v: &Value) -> Result<SlugPolicy> {
+        if let Some(name) = v.as_str() {
+            return match name {
+                "python" => Ok(SlugPolicy::Python),
+                "kebab" => Ok(SlugPolicy::Kebab),
+                "rust-crate" => Ok(SlugPolicy::RustCrate),
+                "npm" => Ok(SlugPolicy::Npm),
+                "go-module" => Ok(SlugPolicy::GoModule),
+                "none" => Ok(SlugPolicy::None),
+                _ => Err(anyhow::anyhow!(format!("Unknown '_slug' policy: {}", name))),
+            };
+        }
+        let obj = v.as_object()
+            .ok_or_else(|| anyhow::anyhow!("'_slug' in copilot.json must be a policy name or an object with a 'pattern'"))?;
+        let pattern = obj.get("pattern").and_then(|p| p.as_str())
+            .ok_or_else(|| anyhow::anyhow!("'_slug' needs a string 'pattern'"))?;
+        let pattern = regex::Regex::new(pattern)
+            .with_context(|| format!("Invalid '_slug' pattern: {}", pattern))?;
+        let replacement = match obj.get("replacement") {
+            None => "_".to_string(),
+            Some(r) => r.as_str().ok_or_else(|| anyhow::anyhow!("'_slug' 'replacement' must be a string"))?.to_string(),
+        };
+        let lowercase = match obj.get("lowercase") {
+            None => true,
+            Some(l) => l.as_bool().ok_or_else(|| anyhow::anyhow!("'_slug' 'lowercase' must be a boolean"))?,
+        };
+        let transliterate = match obj.get("transliterate") {
+            None => true,
+            Some(t) => t.as_bool().ok_or_else(|| anyhow::anyhow!("'_slug' 'transliterate' must be a boolean"))?,
+        };
+        Ok(SlugPolicy::Custom { pattern, replacement, lowercase, transliterate })
+    }
+
+    pub fn apply(&self, input: &str) -> String {
+        // Every policy except "none" romanizes non-ASCII text first ("Café" -> "cafe")
+        let romanized = match self {
+            SlugPolicy::None | SlugPolicy::Custom { transliterate: false, .. } => input.to_string(),
+            _ => crate::util::transliterate(input),
+        };
+        let input = romanized.as_str();
+        match self {
+            SlugPolicy::Python => crate::util::sanitize_slug_python(input),
+            SlugPolicy::Kebab => crate::util::sanitize_slug_kebab(input),
+            SlugPolicy::RustCrate => crate::util::sanitize_slug_rust_crate(input),
+            SlugPolicy::Npm => crate::util::sanitize_slug_npm(input),
+            SlugPolicy::GoModule => crate::util::sanitize_slug_go_module(input),
+            SlugPolicy::None => input.trim().to_string(),
+            SlugPolicy::Custom { pattern, replacement, lowercase, .. } => {
+                let input = if *lowercase { input.to_lowercase() } else { input.to_string() };
+                let slug = pattern.replace_all(&input, regex::NoExpand(replacement));
+                let mut slug = slug.as_ref();
+                if !replacement.is_empty() {
+                    while let Some(rest) = slug.strip_prefix(replacement.as_str()) { slug = rest; }
+                    while let Some(rest) = slug.strip_suffix(replacement.as_str()) { slug = rest; }
+                }
+                slug.to_string()
             }
-            return false;
         }
-        if s.is_empty() { return false; }
-        if segment_matches(p[0], s[0]) { return rec(&p[1..], &s[1..]); }
-        false
     }
-    rec(&psegs, &ssegs)
-}
\ No newline at end of file
+}
+
+// Ordered path rules; the last rule matching either the template path or the
+// rendered path (both relative to the root directory) decides the mode.
+#[derive(Debug, Clone, Default)]
+pub struct PathRules {
+    rules: Vec<(Glob, PathMode)>,
+}
+
+impl PathRules {
+    pub fn mode_for(&self, template_rel: &str, rendered_rel: Option<&str>) -> PathMode {
+        let template_rel = template_rel.replace('\\', "/");
+        let rendered_rel = rendered_rel.map(|r| r.replace('\\', "/"));
+        let mut mode = PathMode::Render;
+        for (glob, m) in &self.rules {
+            let hit = glob.matches(&template_rel)
+                || rendered_rel.as_deref().map(|r| glob.matches(r)).unwrap_or(false);
+            if hit { mode = *m; }
+        }
+        mode
+    }
+}
+
+// Permission overrides for generated files; the last rule matching either the
+// template path or the rendered path (relative to the root directory) wins.
+#[derive(Debug, Clone, Default)]
+pub struct FileModes {
+    rules: Vec<(Glob, u32)>,
+}
+
+impl FileModes {
+    pub fn mode_for(&self, template_rel: &str, rendered_rel: Option<&str>) -> Option<u32> {
+        let template_rel = template_rel.replace('\\', "/");
+        let rendered_rel = rendered_rel.map(|r| r.replace('\\', "/"));
+        self.rules.iter().rev()
+            .find(|(glob, _)| glob.matches(&template_rel) || rendered_rel.as_deref().map(|r| glob.matches(r)).unwrap_or(false))
+            .map(|(_, m)| *m)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    // UTF-16 files always start with a byte order mark
+    Utf16Le,
+    Utf16Be,
+}
+
+// Compile a pattern with .gitattributes semantics: patterns without '/' match the
+// file name at any depth, others are anchored. Negation is not supported.
+fn attribute_glob(field: &str, pattern: &str) -> Result<Glob> {
+    if pattern.starts_with('!') {
+        anyhow::bail!(format!("Negative patterns are not allowed in '{}': {}", field, pattern));
+    }
+    let anchored = if pattern.contains('/') {
+        pattern.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{}", pattern)
+    };
+    Glob::parse(field, &anchored)
+}
+
+// One `_text_attributes` line: a pattern followed by `eol=...` and/or `encoding=...`.
+#[derive(Debug, Clone)]
+struct TextAttributeRule {
+    glob: Glob,
+    eol: Option<LineEnding>,
+    encoding: Option<TextEncoding>,
+}
+
+impl TextAttributeRule {
+    fn parse(line: &str) -> Result<TextAttributeRule> {
+        let mut parts = line.split_whitespace();
+        let pattern = parts.next()
+            .ok_or_else(|| anyhow::anyhow!("Empty line in '_text_attributes'"))?;
+        let glob = attribute_glob("_text_attributes", pattern)?;
+        let mut rule = TextAttributeRule { glob, eol: None, encoding: None };
+        for attr in parts {
+            let (key, value) = attr.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!(format!("Expected attr=value in '_text_attributes' line: {}", line)))?;
+            match key {
+                "eol" => rule.eol = Some(match value.to_ascii_lowercase().as_str() {
+                    "lf" => LineEnding::Lf,
+                    "crlf" => LineEnding::Crlf,
+                    _ => anyhow::bail!(format!("Unknown eol '{}' in '_text_attributes' line: {}", value, line)),
+                }),
+                "encoding" | "working-tree-encoding" => rule.encoding = Some(match value.to_ascii_lowercase().as_str() {
+                    "utf-8" | "utf8" => TextEncoding::Utf8,
+                    "utf-8-bom" | "utf8-bom" => TextEncoding::Utf8Bom,
+                    "utf-16" | "utf-16le" | "utf16le" => TextEncoding::Utf16Le,
+                    "utf-16be" | "utf16be" => TextEncoding::Utf16Be,
+                    _ => anyhow::bail!(format!("Unknown encoding '{}' in '_text_attributes' line: {}", value, line)),
+                }),
+                _ => anyhow::bail!(format!("Unknown attribute '{}' in '_text_attributes' line: {}", key, line)),
+            }
+        }
+        Ok(rule)
+    }
+}
+
+// Line ending and encoding of rendered files. Each attribute is decided by the last
+// line that sets it and matches the path; None keeps the rendered text unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct TextAttributes {
+    rules: Vec<TextAttributeRule>,
+}
+
+impl TextAttributes {
+    pub fn for_path(&self, rel: &str) -> (Option<LineEnding>, Option<TextEncoding>) {
+        let rel = rel.replace('\\', "/");
+        let mut eol = None;
+        let mut encoding = None;
+        for rule in &self.rules {
+            if !rule.glob.matches(&rel) { continue; }
+            eol = rule.eol.or(eol);
+            encoding = rule.encoding.or(encoding);
+        }
+        (eol, encoding)
+    }
+}
+
+// Formatter commands for generated files. Every matching rule runs, in order; "{}" in
+// a command stands for the file path, which is appended when there is no placeholder.
+#[derive(Debug, Clone, Default)]
+pub struct Formatters {
+    rules: Vec<(Glob, Vec<String>)>,
+}
+
+impl Formatters {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // Command argv lists (placeholders unexpanded) that apply to `rel`.
+    pub fn commands_for(&self, rel: &str) -> Vec<&[String]> {
+        let rel = rel.replace('\\', "/");
+        self.rules.iter()
+            .filter(|(glob, _)| glob.matches(&rel))
+            .map(|(_, command)| command.as_slice())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(patterns: &[&str]) -> CopyFilter {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        compile_patterns("test", &patterns).unwrap()
+    }
+
+    #[test]
+    fn copy_filter_last_match_wins() {
+        let f = filter(&["tests/**", "!tests/conftest.py"]);
+        assert!(f.is_match("tests/test_app.py"));
+        assert!(!f.is_match("tests/conftest.py"));
+        assert!(!f.is_match("src/app.py"));
+
+        // A later positive pattern re-includes what a negation removed
+        let f = filter(&["tests/**", "!tests/fixtures/**", "tests/fixtures/keep.json"]);
+        assert!(f.is_match("tests/a.py"));
+        assert!(!f.is_match("tests/fixtures/data.json"));
+        assert!(f.is_match("tests/fixtures/keep.json"));
+
+        // A negation alone matches nothing
+        assert!(!filter(&["!*.md"]).is_match("README.md"));
+        assert!(f.is_match("tests\\windows\\path.py"));
+    }
+
+    #[test]
+    fn file_modes_keep_order_and_last_match_wins() {
+        let manifest = Manifest {
+            file_modes: vec![
+                ("scripts/*.sh".to_string(), "755".to_string()),
+                ("**/*.sh".to_string(), "600".to_string()),
+                ("scripts/run.sh".to_string(), "0o700".to_string()),
+            ],
+            ..Manifest::default()
+        };
+        let modes = manifest.compile_file_modes().unwrap();
+        assert_eq!(modes.mode_for("scripts/b.sh", None), Some(0o600));
+        assert_eq!(modes.mode_for("scripts/run.sh", None), Some(0o700));
+        assert_eq!(modes.mode_for("{{ name }}.sh", Some("demo.sh")), Some(0o600));
+        assert_eq!(modes.mode_for("README.md", None), None);
+    }
+
+    #[test]
+    fn text_attributes_last_line_wins_per_attribute() {
+        let manifest = Manifest {
+            text_attributes: vec![
+                "* eol=lf".to_string(),
+                "*.bat eol=crlf".to_string(),
+                "scripts/*.ps1 encoding=utf-8-bom".to_string(),
+                "*.reg eol=crlf working-tree-encoding=UTF-16".to_string(),
+                "legacy/*.reg encoding=utf-16be".to_string(),
+            ],
+            ..Manifest::default()
+        };
+        let attrs = manifest.compile_text_attributes().unwrap();
+        assert_eq!(attrs.for_path("README.md"), (Some(LineEnding::Lf), None));
+        // Patterns without '/' match at any depth
+        assert_eq!(attrs.for_path("tools/win/run.bat"), (Some(LineEnding::Crlf), None));
+        assert_eq!(attrs.for_path("tools\\win\\run.bat"), (Some(LineEnding::Crlf), None));
+        // Patterns with '/' are anchored to the root; eol still comes from "* eol=lf"
+        assert_eq!(attrs.for_path("scripts/setup.ps1"), (Some(LineEnding::Lf), Some(TextEncoding::Utf8Bom)));
+        assert_eq!(attrs.for_path("other/scripts/setup.ps1"), (Some(LineEnding::Lf), None));
+        assert_eq!(attrs.for_path("keys.reg"), (Some(LineEnding::Crlf), Some(TextEncoding::Utf16Le)));
+        assert_eq!(attrs.for_path("legacy/keys.reg"), (Some(LineEnding::Crlf), Some(TextEncoding::Utf16Be)));
+        assert_eq!(TextAttributes::default().for_path("a.txt"), (None, None));
+    }
+
+    #[test]
+    fn text_attributes_reject_bad_lines() {
+        for line in ["*.txt eol=cr", "*.txt encoding=latin1", "*.txt text", "*.txt charset=utf-8", "!*.txt eol=lf"] {
+            let manifest = Manifest { text_attributes: vec![line.to_string()], ..Manifest::default() };
+            assert!(manifest.compile_text_attributes().is_err(), "{}", line);
+        }
+    }
+
+    fn manifest_from(json: &str) -> Result<Manifest> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("copilot.json"), json).unwrap();
+        load_manifest(dir.path())
+    }
+
+    #[test]
+    fn exclude_rules_keep_order() {
+        let vars: BTreeMap<String, Value> = [("docs".to_string(), Value::Bool(false))].into_iter().collect();
+        let manifest = manifest_from(r#"{"_exclude": [{"pattern": "docs/**", "if": "not docs"}, "!docs/keep.md", {"pattern": "ci/**", "if": false}]}"#).unwrap();
+        let f = manifest.compile_exclude_filter(&vars).unwrap();
+        assert!(f.is_match("docs/index.md"));
+        assert!(!f.is_match("docs/keep.md"));
+        assert!(!f.is_match("ci/build.yml"));
+
+        // Object keys come back sorted, so negations there are rejected
+        let err = manifest_from(r#"{"_exclude": {"docs/**": true, "!docs/keep.md": true}}"#).unwrap_err();
+        assert!(err.to_string().contains("!docs/keep.md"), "{}", err);
+        let manifest = manifest_from(r#"{"_exclude": {"docs/**": "not docs"}}"#).unwrap();
+        assert!(manifest.compile_exclude_filter(&vars).unwrap().is_match("docs/a.md"));
+    }
+}
@@ -1,15 +1,58 @@
 use anyhow::{Result, Context, anyhow};
 use std::{path::{Path, PathBuf}, fs};
 use walkdir::WalkDir;
-use minijinja::Environment;
 use serde::Serialize;
 use serde_json::to_value as to_json_value;
-use crate::manifest::CopyFilter;
-use crate::util::{sanitize_slug_python, is_safe_path_segment};
+use crate::manifest::{CopyFilter, FileModes, JinjaOptions, LineEnding, PathMode, PathRules, SlugPolicy, TextAttributes, TextEncoding};
+use crate::hooks::{FileHooks, FileHookOutcome};
+use crate::ignore::{IgnoreFile, IGNORE_FILE_NAME};
+use crate::diagnostics::describe_template_error;
+use std::collections::{BTreeMap, HashMap};
+use crate::util::{is_safe_path_segment, link_or_copy, encode_text, copy_permissions, set_file_mode, create_symlink, symlink_stays_within, parallel_map};
 
-pub fn render_all<T: Serialize>(template_dir: &Path, output_dir: &Path, vars: &T, copy_filter: &CopyFilter) -> Result<()> {
-    let mut env = Environment::new();
-    // Normalize and enforce Python-importable project_slug in vars
+// Settings for a render_all run, derived from the manifest and hooks.
+pub struct RenderOptions<'a> {
+    // Per-path rendering modes (`_copy_without_render` and `_path_modes`)
+    pub path_rules: &'a PathRules,
+    // Active `_exclude` patterns (conditions already evaluated)
+    pub exclude_filter: &'a CopyFilter,
+    pub file_modes: &'a FileModes,
+    pub text_attributes: &'a TextAttributes,
+    // `_fragments` directories and `_template_suffixes`
+    pub fragment_dirs: &'a CopyFilter,
+    pub template_suffixes: &'a [String],
+    pub file_hooks: &'a FileHooks,
+    // Template root entries to emit (see Manifest::roots); empty means auto-detect
+    // the single '{{ project_slug }}' directory.
+    pub roots: &'a [String],
+    pub jinja: &'a JinjaOptions,
+    // Normalization applied to project_slug (`_slug`)
+    pub slug: &'a SlugPolicy,
+    // Directory (relative to the template root) whose templates are registered for
+    // include/import/extends (through the loader) but never emitted
+    pub partials_dir: &'a str,
+    // Number of worker threads for writing the output (at least 1)
+    pub jobs: usize,
+    pub verbose: bool,
+}
+
+// A rendered file whose `_text_attributes` are applied once hooks and formatters have
+// seen its UTF-8 text (see encode_outputs).
+pub struct TextOutput {
+    pub rel: PathBuf,
+    pub eol: Option<LineEnding>,
+    pub encoding: Option<TextEncoding>,
+}
+
+// Render the template tree into `output_dir`. Rendered files are written as UTF-8 with the
+// template's line endings; the returned list says which of them need converting.
+pub fn render_all<T: Serialize>(template_dir: &Path, output_dir: &Path, vars: &T, opts: &RenderOptions) -> Result<Vec<TextOutput>> {
+    let path_rules = opts.path_rules;
+    let file_hooks = opts.file_hooks;
+    let mut env = opts.jinja.environment()?;
+    // Keep template sources on errors for line/column diagnostics
+    env.set_debug(true);
+    // Normalize project_slug in vars according to the `_slug` policy
     let mut vars_json = to_json_value(vars).with_context(|| "Failed to serialize template variables")?;
     // Acquire project_slug with graceful fallback from project_title/project_name
     let slug_source: String = match vars_json.get("project_slug").and_then(|v| v.as_str()) {
@@ -19,10 +62,10 @@ pub fn render_all<T: Serialize>(template_dir: &Path, output_dir: &Path, vars: &T
                 .get("project_title").and_then(|v| v.as_str())
                 .or_else(|| vars_json.get("project_name").and_then(|v| v.as_str()))
                 .unwrap_or("project");
-            sanitize_slug_python(fallback)
+            opts.slug.apply(fallback)
         }
     };
-    let normalized_slug = sanitize_slug_python(&slug_source);
+    let normalized_slug = opts.slug.apply(&slug_source);
     if normalized_slug.is_empty() {
         anyhow::bail!("Invalid 'project_slug' after normalization: empty");
     }
@@ -30,30 +73,30 @@ pub fn render_all<T: Serialize>(template_dir: &Path, output_dir: &Path, vars: &T
         map.insert("project_slug".to_string(), serde_json::Value::String(normalized_slug.clone()));
     }
 
-    // Detect the single main project directory that contains Jinja variables for {{ project_slug }}
-    let mut main_dir_tpl: Option<String> = None;
-    for entry in fs::read_dir(template_dir).with_context(|| format!("Failed to read directory: {}", template_dir.display()))? {
-        let entry = entry.with_context(|| "Failed to iterate template root")?;
-        let md = entry.metadata().with_context(|| "Failed to read entry metadata")?;
-        if !md.is_dir() { continue; }
-        let name = entry.file_name();
-        let name_str = name.to_string_lossy().to_string();
-        if name_str.contains("{{") && name_str.contains("}}") && name_str.contains("project_slug") {
-            if main_dir_tpl.is_some() {
-                anyhow::bail!("Multiple main project directories detected. Only one '{{ project_slug }}' directory is supported.");
-            }
-            main_dir_tpl = Some(name_str);
-        }
-    }
-    let main_dir_tpl = main_dir_tpl.ok_or_else(|| anyhow!("Main project directory using '{{ project_slug }}' not found at template root"))?;
-    // Pass 1: collect templates and register into environment (to support extends/include/import)
-    struct Item { name: String, rel: PathBuf, src_path: PathBuf, copy_raw: bool }
-    struct PendingTpl { name: String, content: String, has_extends: bool }
+    // Determine which template root entries are emitted
+    let roots = resolve_roots(template_dir, opts.roots, opts.jinja)?;
+    // "." renders the whole template root directly into the output root
+    let whole_root = roots.iter().any(|r| r == ".");
+    // `.copilotignore` at the template root (relative to the template root) and inside
+    // each top-level directory such as '{{ project_slug }}' (relative to that directory)
+    let root_ignore = IgnoreFile::load(&template_dir.join(IGNORE_FILE_NAME))?;
+    let mut dir_ignores: HashMap<String, Option<IgnoreFile>> = HashMap::new();
+    // Pass 1: collect templates; their (hook-transformed) sources are served by the loader
+    // `fragments` lists the templates concatenated into this file (see fragment_target);
+    // when empty the item renders the template `name` itself.
+    struct Item { name: String, rel: PathBuf, src_path: PathBuf, copy_raw: bool, mode: Option<u32>, text: (Option<LineEnding>, Option<TextEncoding>), link_target: Option<PathBuf>, fragments: Vec<String> }
     let mut items: Vec<Item> = Vec::new();
-    let mut to_register: Vec<PendingTpl> = Vec::new();
-    for entry in WalkDir::new(template_dir).into_iter().filter_map(|e| e.ok()) {
+    // Merged file path -> (fragment file name, template name, source path)
+    let mut fragments: BTreeMap<PathBuf, Vec<(String, String, PathBuf)>> = BTreeMap::new();
+    let mut sources: HashMap<String, String> = HashMap::new();
+    // Loader name -> path relative to the template root, for diagnostics
+    let mut template_paths: HashMap<String, String> = HashMap::new();
+    // Path rendering failures, keyed by the number of items collected before them
+    let mut path_failures: Vec<(usize, String)> = Vec::new();
+    'files: for entry in WalkDir::new(template_dir).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
         let path = entry.path();
-        if path.is_dir() { continue; }
+        let is_link = entry.path_is_symlink();
+        if entry.file_type().is_dir() { continue; }
         let rel = path
             .strip_prefix(template_dir)
             .with_context(|| format!("Failed to compute relative path: {}", path.display()))?;
@@ -63,55 +106,239 @@ pub fn render_all<T: Serialize>(template_dir: &Path, output_dir: &Path, vars: &T
         if let Some(first) = rel.components().next() {
             if first.as_os_str() == "hooks" { continue; }
         }
-        // Filter: only process files under the main project directory (single main project)
-        if let Some(first) = rel.components().next() {
-            let first_str = first.as_os_str().to_string_lossy();
-            if first_str != main_dir_tpl {
-                // Skip any content not under the main project dir
-                continue;
+        // Partials are registered separately and never emitted
+        if rel.starts_with(opts.partials_dir) { continue; }
+        // Filter: only process files under the configured root directories
+        if !whole_root {
+            if let Some(first) = rel.components().next() {
+                let first_str = first.as_os_str().to_string_lossy();
+                if !roots.iter().any(|r| *r == first_str) {
+                    // Skip any content not under a root dir
+                    continue;
+                }
+            }
+        }
+
+        // Split off the root directory component: patterns are relative to it.
+        // Example: pattern "tests/**" should match "{{ project_slug }}/tests/**" paths.
+        let mut comps = rel.components();
+        let root_comp = if whole_root { None } else { comps.next() };
+        let inner_rel: PathBuf = comps.collect();
+        let inner_rel_str = inner_rel.to_string_lossy().replace('\\', "/");
+
+        // Ignore files themselves are never generated
+        if path.file_name().map(|n| n == IGNORE_FILE_NAME).unwrap_or(false) {
+            continue;
+        }
+        if let Some(ig) = &root_ignore {
+            if ig.is_ignored(&rel.to_string_lossy()) { continue; }
+        }
+        let mut top_comps = rel.components();
+        let top = top_comps.next().map(|c| c.as_os_str().to_string_lossy().to_string());
+        let below_top: PathBuf = top_comps.collect();
+        if let (Some(top), false) = (top, below_top.as_os_str().is_empty()) {
+            if !dir_ignores.contains_key(&top) {
+                let loaded = IgnoreFile::load(&template_dir.join(&top).join(IGNORE_FILE_NAME))?;
+                dir_ignores.insert(top.clone(), loaded);
+            }
+            if let Some(Some(ig)) = dir_ignores.get(&top) {
+                if ig.is_ignored(&below_top.to_string_lossy()) { continue; }
             }
         }
 
         // Render each segment of the relative path to get the final name
+        let render_segments = |p: &Path| -> std::result::Result<Vec<String>, minijinja::Error> {
+            p.components()
+                .map(|comp| env.render_str(&comp.as_os_str().to_string_lossy(), &vars_json))
+                .collect()
+        };
+        // Path and symlink target errors are reported with the template errors of pass 2
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+        let path_failure = |what: &str, e: minijinja::Error| {
+            format!("while rendering the {} of {}:\n{}", what, rel_str, describe_template_error(&e, &vars_json, &|_: &str| rel_str.clone()))
+        };
+        let root_segment = match root_comp {
+            Some(c) => match render_segments(Path::new(c.as_os_str())) {
+                Ok(mut segments) => segments.pop(),
+                Err(e) => {
+                    path_failures.push((items.len(), path_failure("path", e)));
+                    continue;
+                }
+            },
+            None => None,
+        };
+        // Rendering errors only matter once the path mode says the name is rendered
+        let rendered_inner_segments = render_segments(&inner_rel);
+        let rendered_match_str = rendered_inner_segments.as_ref().ok().map(|segs| segs.join("/"));
+
+        // _exclude patterns whose condition holds apply to both the template and the rendered path
+        let excluded = opts.exclude_filter.is_match(&inner_rel_str)
+            || rendered_match_str.as_deref().map(|r| opts.exclude_filter.is_match(r)).unwrap_or(false);
+        if excluded {
+            continue;
+        }
+        let path_mode = path_rules.mode_for(&inner_rel_str, rendered_match_str.as_deref());
+        if path_mode == PathMode::Exclude {
+            continue;
+        }
+        let inner_segments: Vec<String> = if path_mode.renders_name() {
+            match rendered_inner_segments {
+                Ok(segments) => segments,
+                Err(e) => {
+                    path_failures.push((items.len(), path_failure("path", e)));
+                    continue;
+                }
+            }
+        } else {
+            inner_rel.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect()
+        };
+
         let mut rendered_rel = PathBuf::new();
-        for comp in rel.components() {
-            let comp_str = comp.as_os_str().to_string_lossy();
-            let out_segment = env.render_str(&comp_str, &vars_json)
-                .with_context(|| format!("Failed to render path segment: {}", comp_str))?;
-            if !is_safe_path_segment(&out_segment) {
+        let mut rendered_inner = PathBuf::new();
+        for (i, out_segment) in root_segment.iter().chain(inner_segments.iter()).enumerate() {
+            // A segment rendering to nothing (e.g. `{% if use_docker %}Dockerfile{% endif %}`)
+            // omits the path together with its whole subtree.
+            if out_segment.trim().is_empty() {
+                continue 'files;
+            }
+            if !is_safe_path_segment(out_segment) {
                 anyhow::bail!(format!("Unsafe rendered path segment: {}", out_segment));
             }
             rendered_rel.push(out_segment);
+            if i > 0 || root_segment.is_none() {
+                rendered_inner.push(out_segment);
+            }
+        }
+        let copy_raw = !path_mode.renders_content();
+        // Template-only suffixes are dropped from rendered files: `config.yaml.j2` -> `config.yaml`
+        if !copy_raw && !is_link {
+            if let Some(stripped) = strip_template_suffix(&rendered_rel, opts.template_suffixes) {
+                rendered_rel.set_file_name(&stripped);
+                rendered_inner.set_file_name(&stripped);
+            }
         }
         let name_owned = rendered_rel.to_string_lossy().replace('\\', "/");
-        // Apply _copy_without_render patterns relative to the main project directory.
-        // Example: pattern "tests/**" should match "{{ project_slug }}/tests/**" paths.
-        let mut comps = rel.components();
-        let _first = comps.next(); // strip the main project directory component
-        let inner_rel: std::path::PathBuf = comps.collect();
-        let inner_rel_str = inner_rel.to_string_lossy().replace('\\', "/");
-        let copy_raw = copy_filter.is_match(&inner_rel_str);
+        let rendered_inner_str = rendered_inner.to_string_lossy().replace('\\', "/");
+        let mode = opts.file_modes.mode_for(&inner_rel_str, Some(&rendered_inner_str));
+        // Line ending and encoding applied to the rendered content (`_text_attributes`)
+        let text = opts.text_attributes.for_path(&rendered_inner_str);
+
+        // Symlinks are reproduced as symlinks; their (rendered) target must be
+        // relative and stay inside the root directory the link belongs to.
+        if is_link {
+            let raw_target = fs::read_link(path)
+                .with_context(|| format!("Failed to read symlink: {}", path.display()))?;
+            let raw_target_str = raw_target.to_string_lossy();
+            let target = if copy_raw {
+                raw_target_str.to_string()
+            } else {
+                match env.render_str(&raw_target_str, &vars_json) {
+                    Ok(t) => t,
+                    Err(e) => {
+                        path_failures.push((items.len(), path_failure("symlink target", e)));
+                        continue;
+                    }
+                }
+            };
+            let target = PathBuf::from(target);
+            if !symlink_stays_within(&rendered_inner, &target) {
+                anyhow::bail!(format!("Symlink target escapes the project: {} -> {}", name_owned, target.display()));
+            }
+            items.push(Item { name: name_owned, rel: rendered_rel, src_path: path.to_path_buf(), copy_raw: true, mode: None, text: (None, None), link_target: Some(target), fragments: Vec::new() });
+            continue;
+        }
 
         if !copy_raw {
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("Failed to read template file: {}", path.display()))?;
-            let has_extends = content.contains("{% extends");
-            to_register.push(PendingTpl { name: name_owned.clone(), content, has_extends });
+            // Binary files (NUL bytes or invalid UTF-8) are copied verbatim. A NUL in the
+            // first block settles it without reading large assets into memory.
+            let bytes = if has_binary_prefix(path)? {
+                None
+            } else {
+                Some(fs::read(path)
+                    .with_context(|| format!("Failed to read template file: {}", path.display()))?)
+            };
+            let content = match bytes.and_then(text_content) {
+                Some(c) => c,
+                None => {
+                    if opts.verbose {
+                        eprintln!("Warning: copying binary file without rendering: {}", rel.display());
+                    }
+                    items.push(Item { name: name_owned, rel: rendered_rel, src_path: path.to_path_buf(), copy_raw: true, mode, text, link_target: None, fragments: Vec::new() });
+                    continue;
+                }
+            };
+            // pre_render_file hook may transform the source or drop the file entirely
+            let content = match file_hooks.pre_render(&vars_json, &name_owned, content)? {
+                FileHookOutcome::Keep(c) => c,
+                FileHookOutcome::Skip => continue,
+            };
+            sources.insert(name_owned.clone(), content);
+            template_paths.insert(name_owned.clone(), rel.to_string_lossy().replace('\\', "/"));
+            if let Some(target) = fragment_target(&rendered_rel, &rendered_inner, &inner_rel, opts.fragment_dirs) {
+                let file_name = rendered_rel.file_name().unwrap_or_default().to_string_lossy().to_string();
+                fragments.entry(target).or_default().push((file_name, name_owned, path.to_path_buf()));
+                continue;
+            }
         }
-        items.push(Item { name: name_owned, rel: rendered_rel, src_path: path.to_path_buf(), copy_raw });
+        items.push(Item { name: name_owned, rel: rendered_rel, src_path: path.to_path_buf(), copy_raw, mode, text, link_target: None, fragments: Vec::new() });
     }
 
-    // Register templates: first those without extends (likely bases), then those with extends
-    to_register.sort_by_key(|t| t.has_extends);
-    for t in to_register {
-        // Leak to 'static to satisfy MiniJinja environment lifetimes
-        let leaked_name: &'static str = Box::leak(t.name.into_boxed_str());
-        let leaked_content: &'static str = Box::leak(t.content.into_boxed_str());
-        env.add_template(leaked_name, leaked_content)
-            .with_context(|| format!("Failed to add template: {}", leaked_name))?;
+    // Files of a `_fragments` directory `X.d` become one file X, concatenated in file name
+    // order. A rendered template X next to the directory comes first.
+    for (target_rel, mut parts) in fragments {
+        parts.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut names: Vec<String> = parts.iter().map(|(_, name, _)| name.clone()).collect();
+        let mut src_path = parts[0].2.clone();
+        if let Some(pos) = items.iter().position(|i| i.rel == target_rel && !i.copy_raw && i.link_target.is_none()) {
+            let base = items.remove(pos);
+            names.insert(0, base.name);
+            src_path = base.src_path;
+        }
+        let inner: PathBuf = if whole_root { target_rel.clone() } else { target_rel.components().skip(1).collect() };
+        let inner_str = inner.to_string_lossy().replace('\\', "/");
+        items.push(Item {
+            name: target_rel.to_string_lossy().replace('\\', "/"),
+            rel: target_rel,
+            src_path,
+            copy_raw: false,
+            mode: opts.file_modes.mode_for(&inner_str, None),
+            text: opts.text_attributes.for_path(&inner_str),
+            link_target: None,
+            fragments: names,
+        });
     }
 
-    // Templates are registered in the environment
+    // Resolve include/import/extends targets lazily by name, so templates may reference
+    // each other in any order: project templates first, then shared partials (relative to
+    // the partials directory), then any other file of the staged template tree.
+    let loader_root = template_dir.to_path_buf();
+    let partials_root = template_dir.join(opts.partials_dir);
+    let display_name = |name: &str| -> String {
+        if let Some(p) = template_paths.get(name) { return p.clone(); }
+        if crate::util::is_safe_rel_path(name) && partials_root.join(name).is_file() {
+            return format!("{}/{}", opts.partials_dir.trim_end_matches('/'), name);
+        }
+        name.to_string()
+    };
+    let partials_root_loader = partials_root.clone();
+    env.set_loader(move |name| {
+        if let Some(content) = sources.get(name) {
+            return Ok(Some(content.clone()));
+        }
+        if !crate::util::is_safe_rel_path(name) {
+            return Ok(None);
+        }
+        for base in [&partials_root_loader, &loader_root] {
+            let path = base.join(name);
+            if path.is_file() {
+                return fs::read_to_string(&path).map(Some).map_err(|e| {
+                    minijinja::Error::new(minijinja::ErrorKind::InvalidOperation, format!("Failed to read template: {}", path.display()))
+                        .with_source(e)
+                });
+            }
+        }
+        Ok(None)
+    });
 
     // Ensure output root exists and cache canonical root for performance
     std::fs::create_dir_all(output_dir)
@@ -119,28 +346,199 @@ pub fn render_all<T: Serialize>(template_dir: &Path, output_dir: &Path, vars: &T
     let output_canon = output_dir.canonicalize()
         .with_context(|| format!("Failed to canonicalize output root: {}", output_dir.display()))?;
 
-    // Pass 2: render or copy into the destination using the final names
-    for item in items {
+    // When several template files render to the same path the last one wins, as it
+    // would when writing sequentially; keep only that one so workers never race on it.
+    let mut last_for_rel: HashMap<&Path, usize> = HashMap::new();
+    for (i, item) in items.iter().enumerate() {
+        last_for_rel.insert(item.rel.as_path(), i);
+    }
+    let items: Vec<(usize, &Item)> = items.iter().enumerate()
+        .filter(|(i, item)| last_for_rel[item.rel.as_path()] == *i)
+        .collect();
+
+    // Pass 2: render or copy into the destination using the final names, on a pool of
+    // `opts.jobs` workers. Template errors are collected so a single run reports every
+    // failing file; all errors are reported in template walk order.
+    let render_item = |&(_, item): &(usize, &Item)| -> Result<Option<String>> {
         let target_path = crate::util::safe_resolve_under_canon(&output_canon, &item.rel)?;
+        if let Some(link_target) = &item.link_target {
+            if let Some(parent) = target_path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+            }
+            let is_dir = fs::metadata(&item.src_path).map(|m| m.is_dir()).unwrap_or(false);
+            create_symlink(link_target, &target_path, is_dir)?;
+            return Ok(None);
+        }
+        let rendered = if item.copy_raw {
+            None
+        } else {
+            let render = |name: &str| env.get_template(name).and_then(|tpl| tpl.render(&vars_json));
+            // Errors inside imported macros or included files point at those templates;
+            // name the file being rendered as well
+            let failure = |name: &str, e: minijinja::Error| {
+                format!("while rendering {}:\n{}", display_name(name), describe_template_error(&e, &vars_json, &display_name))
+            };
+            let rendered = if item.fragments.is_empty() {
+                match render(&item.name) {
+                    Ok(r) => r,
+                    Err(e) => return Ok(Some(failure(&item.name, e))),
+                }
+            } else {
+                // Blank fragments (e.g. a disabled `{% if %}` feature) contribute nothing;
+                // every other fragment ends with a newline
+                let mut merged = String::new();
+                let mut errors = Vec::new();
+                for name in &item.fragments {
+                    match render(name) {
+                        Ok(part) if part.trim().is_empty() => {}
+                        Ok(part) => {
+                            merged.push_str(&part);
+                            if !part.ends_with('\n') { merged.push('\n'); }
+                        }
+                        Err(e) => errors.push(failure(name, e)),
+                    }
+                }
+                if !errors.is_empty() {
+                    return Ok(Some(errors.join("\n\n")));
+                }
+                merged
+            };
+            // post_render_file hook may transform the output or drop the file entirely
+            match file_hooks.post_render(&vars_json, &item.name, rendered)? {
+                FileHookOutcome::Keep(c) => Some(c),
+                FileHookOutcome::Skip => return Ok(None),
+            }
+        };
         if let Some(parent) = target_path.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
         }
-        if item.copy_raw {
-            let bytes = fs::read(&item.src_path)
-                .with_context(|| format!("Failed to read template file: {}", item.src_path.display()))?;
-            fs::write(&target_path, bytes)
-                .with_context(|| format!("Failed to write file: {}", target_path.display()))?;
-        } else {
-            let tpl = env.get_template(&item.name)
-                .with_context(|| format!("Failed to get template: {}", item.name))?;
-            let rendered = tpl.render(&vars_json)
-                .with_context(|| format!("Failed to render file: {}", item.src_path.display()))?;
-            fs::write(&target_path, rendered)
-                .with_context(|| format!("Failed to write file: {}", target_path.display()))?;
+        match rendered {
+            None => {
+                // Verbatim copies keep permissions and modification time; the source is
+                // the temporary template copy, so it can be hard-linked into staging
+                link_or_copy(&item.src_path, &target_path)?;
+            }
+            Some(rendered) => {
+                fs::write(&target_path, rendered)
+                    .with_context(|| format!("Failed to write file: {}", target_path.display()))?;
+                // Rendered files keep the source permissions (e.g. executable scripts)
+                copy_permissions(&item.src_path, &target_path)?;
+            }
+        }
+        if let Some(mode) = item.mode {
+            set_file_mode(&target_path, mode)?;
+        }
+        Ok(None)
+    };
+    // A path failure precedes the item collected right after it
+    let mut failures: Vec<((usize, bool), String)> = path_failures.into_iter()
+        .map(|(pos, failure)| ((pos, false), failure))
+        .collect();
+    for (result, (index, _)) in parallel_map(&items, opts.jobs, render_item).into_iter().zip(&items) {
+        if let Some(failure) = result? {
+            failures.push(((*index, true), failure));
+        }
+    }
+    failures.sort_by_key(|(key, _)| *key);
+    if !failures.is_empty() {
+        for (_, f) in &failures {
+            eprintln!("{}\n", f);
         }
+        anyhow::bail!("{} template(s) failed to render", failures.len());
+    }
+    let outputs = items.iter()
+        .filter(|(_, item)| !item.copy_raw && item.text != (None, None))
+        .map(|(_, item)| TextOutput { rel: item.rel.clone(), eol: item.text.0, encoding: item.text.1 })
+        .collect();
+    Ok(outputs)
+}
+
+// Convert rendered files to their `_text_attributes` line endings and encoding. This is
+// the last step before the move to the output, so formatters cannot undo it. Files that
+// hooks removed or replaced with non-UTF-8 content are left alone.
+pub fn encode_outputs(output_dir: &Path, outputs: &[TextOutput]) -> Result<()> {
+    for output in outputs {
+        let path = output_dir.join(&output.rel);
+        let bytes = match fs::read(&path) {
+            Ok(b) => b,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read file: {}", path.display())),
+        };
+        let Ok(text) = String::from_utf8(bytes) else { continue };
+        fs::write(&path, encode_text(text, output.eol, output.encoding))
+            .with_context(|| format!("Failed to write file: {}", path.display()))?;
     }
     Ok(())
 }
 
-// slug normalization moved to util::sanitize_slug_python
\ No newline at end of file
+// For a fragment such as `Cargo.toml.d/10-serde.j2`, the file it is merged into
+// (`Cargo.toml`): files directly inside a directory named `X.d` that matches one of the
+// `_fragments` patterns (by template or rendered path relative to the root).
+fn fragment_target(rendered_rel: &Path, rendered_inner: &Path, inner_rel: &Path, fragment_dirs: &CopyFilter) -> Option<PathBuf> {
+    let template_dir = inner_rel.parent()?.to_string_lossy().replace('\\', "/");
+    let rendered_dir = rendered_inner.parent()?.to_string_lossy().replace('\\', "/");
+    if !fragment_dirs.is_match(&template_dir) && !fragment_dirs.is_match(&rendered_dir) { return None; }
+    let dir = rendered_rel.parent()?;
+    let dir_name = dir.file_name()?.to_string_lossy();
+    let target_name = dir_name.strip_suffix(".d").filter(|n| !n.is_empty())?;
+    Some(dir.with_file_name(target_name))
+}
+
+// File name of `rel` without the first matching template-only suffix, if any.
+fn strip_template_suffix(rel: &Path, suffixes: &[String]) -> Option<String> {
+    let name = rel.file_name()?.to_string_lossy();
+    suffixes.iter()
+        .filter_map(|suffix| name.strip_suffix(suffix.as_str()))
+        .find(|stripped| !stripped.is_empty())
+        .map(|stripped| stripped.to_string())
+}
+
+// Whether the first block of a file contains a NUL byte.
+fn has_binary_prefix(path: &Path) -> Result<bool> {
+    use std::io::Read;
+    let mut buf = [0u8; 8192];
+    let mut file = fs::File::open(path)
+        .with_context(|| format!("Failed to read template file: {}", path.display()))?;
+    let n = file.read(&mut buf)
+        .with_context(|| format!("Failed to read template file: {}", path.display()))?;
+    Ok(buf[..n].contains(&0))
+}
+
+// Template root entries to emit (see Manifest::roots). Without explicit roots, detect
+// the single main project directory whose name contains {{ project_slug }}.
+pub fn resolve_roots(template_dir: &Path, roots: &[String], jinja: &JinjaOptions) -> Result<Vec<String>> {
+    if roots.is_empty() {
+        let (var_start, var_end) = jinja.variable_delimiters();
+        let mut main_dir_tpl: Option<String> = None;
+        for entry in fs::read_dir(template_dir).with_context(|| format!("Failed to read directory: {}", template_dir.display()))? {
+            let entry = entry.with_context(|| "Failed to iterate template root")?;
+            let md = entry.metadata().with_context(|| "Failed to read entry metadata")?;
+            if !md.is_dir() { continue; }
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy().to_string();
+            if name_str.contains(var_start) && name_str.contains(var_end) && name_str.contains("project_slug") {
+                if main_dir_tpl.is_some() {
+                    anyhow::bail!("Multiple main project directories detected. List them in '_roots' in copilot.json to emit several.");
+                }
+                main_dir_tpl = Some(name_str);
+            }
+        }
+        Ok(vec![main_dir_tpl.ok_or_else(|| anyhow!("Main project directory using '{{ project_slug }}' not found at template root"))?])
+    } else {
+        for r in roots {
+            if r != "." && !template_dir.join(r).is_dir() {
+                anyhow::bail!(format!("Root directory listed in '_roots' not found at template root: {}", r));
+            }
+        }
+        Ok(roots.to_vec())
+    }
+}
+
+// Decode file bytes as text, or None when the file looks binary.
+fn text_content(bytes: Vec<u8>) -> Option<String> {
+    if bytes.contains(&0) { return None; }
+    String::from_utf8(bytes).ok()
+}
+
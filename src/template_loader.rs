@@ -50,11 +50,15 @@ pub fn copy_to_temp_root(src_root: &Path) -> Result<(TempDir, PathBuf)> {
         if rel.components().any(|c| c.as_os_str() == ".git") { continue; }
         if rel.components().any(|c| c.as_os_str() == ".svn") { continue; }
         let target = dst.join(rel);
-        if path.is_dir() {
+        if entry.depth() > 0 && entry.path_is_symlink() {
+            // Keep symlinks as symlinks; the renderer validates their targets
+            if let Some(parent) = target.parent() { fs::create_dir_all(parent)?; }
+            crate::util::copy_symlink(path, &target)?;
+        } else if path.is_dir() {
             fs::create_dir_all(&target)?;
         } else {
             if let Some(parent) = target.parent() { fs::create_dir_all(parent)?; }
-            fs::copy(path, &target)?;
+            crate::util::copy_file_preserving(path, &target)?;
         }
     }
     Ok((temp, dst))
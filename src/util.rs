@@ -19,8 +19,203 @@ pub fn sanitize_slug_python(input: &str) -> String {
     final_slug
 }
 
+// Romanize non-ASCII text before slugging: "Café Über" -> "Cafe Ueber", "Привет" -> "Privet".
+// German umlauts use their conventional two-letter spellings; everything else goes
+// through deunicode (accents dropped, Cyrillic/Greek/CJK romanized, ß -> ss).
+pub fn transliterate(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            'ä' => out.push_str("ae"),
+            'ö' => out.push_str("oe"),
+            'ü' => out.push_str("ue"),
+            'Ä' => out.push_str("Ae"),
+            'Ö' => out.push_str("Oe"),
+            'Ü' => out.push_str("Ue"),
+            c if c.is_ascii() => out.push(c),
+            c => out.push_str(deunicode::deunicode_char(c).unwrap_or("_")),
+        }
+    }
+    out
+}
+
+// Transliterated, lowercase words joined by `sep`; backs the `slugify` Jinja filter.
+pub fn slugify(input: &str, sep: char) -> String {
+    sanitize_slug_with(&transliterate(input), |c| c.is_ascii_lowercase() || c.is_ascii_digit(), sep, &[])
+}
+
+// Lowercase `input`, replace every character not accepted by `keep` with `sep`,
+// collapse runs of `sep` and trim separators and `trim` characters from both ends.
+fn sanitize_slug_with(input: &str, keep: impl Fn(char) -> bool, sep: char, trim: &[char]) -> String {
+    let mut out = String::new();
+    for ch in input.chars().flat_map(|c| c.to_lowercase()) {
+        let c = if keep(ch) { ch } else { sep };
+        if c == sep && out.ends_with(sep) { continue; }
+        out.push(c);
+    }
+    out.trim_matches(|c| c == sep || trim.contains(&c)).to_string()
+}
+
+// Lowercase words joined by '-': "My Service" -> "my-service"
+pub fn sanitize_slug_kebab(input: &str) -> String {
+    sanitize_slug_with(input, |c| c.is_ascii_lowercase() || c.is_ascii_digit(), '-', &[])
+}
+
+// Cargo package name: ascii letters, digits, '-' and '_', not starting with a digit
+pub fn sanitize_slug_rust_crate(input: &str) -> String {
+    let mut slug = sanitize_slug_with(input, |c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_', '-', &['_']);
+    if slug.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+        slug.insert(0, '_');
+    }
+    slug
+}
+
+// Unscoped npm package name: lowercase URL-safe characters, not starting with '.' or '_',
+// at most 214 characters
+pub fn sanitize_slug_npm(input: &str) -> String {
+    let slug = sanitize_slug_with(input, |c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._~".contains(c), '-', &['.', '_']);
+    slug.chars().take(214).collect::<String>().trim_end_matches(['-', '.']).to_string()
+}
+
+// Last element of a Go module path: lowercase letters, digits and "-._~",
+// not starting or ending with '.'
+pub fn sanitize_slug_go_module(input: &str) -> String {
+    sanitize_slug_with(input, |c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._~".contains(c), '-', &['.'])
+}
+
 use std::path::{Path, PathBuf, Component};
-use anyhow::{Result, anyhow};
+use std::fs;
+use anyhow::{Result, Context, anyhow};
+use crate::manifest::{LineEnding, TextEncoding};
+
+// Copy a file, keeping its permissions (std::fs::copy already does) and its
+// modification time. Timestamps are best-effort: failures to set them are ignored.
+pub fn copy_file_preserving(src: &Path, dst: &Path) -> Result<()> {
+    fs::copy(src, dst)
+        .with_context(|| format!("Failed to copy {} to {}", src.display(), dst.display()))?;
+    if let Ok(mtime) = fs::metadata(src).and_then(|m| m.modified()) {
+        // Windows needs write access to change file times
+        if let Ok(f) = fs::OpenOptions::new().write(true).open(dst) {
+            let _ = f.set_modified(mtime);
+        }
+    }
+    Ok(())
+}
+
+// Place a verbatim copy of `src` at `dst`, hard-linking when possible so large assets
+// are neither read into memory nor written twice. Only use this for sources that are
+// our own temporary copies: the link shares the inode (and later mode changes) with
+// `src`. Falls back to a streamed copy, which reflinks on filesystems that support it.
+pub fn link_or_copy(src: &Path, dst: &Path) -> Result<()> {
+    if fs::hard_link(src, dst).is_ok() {
+        return Ok(());
+    }
+    copy_file_preserving(src, dst)
+}
+
+// Move `src` to `dst` (replacing an existing file), or copy it when a rename is not
+// possible, e.g. across filesystems.
+pub fn move_or_copy(src: &Path, dst: &Path) -> Result<()> {
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+    copy_file_preserving(src, dst)
+}
+
+// Convert rendered text to the bytes written to disk: normalize line endings when
+// `eol` is set, then encode. Explicit encodings replace any BOM in the text itself.
+pub fn encode_text(content: String, eol: Option<LineEnding>, encoding: Option<TextEncoding>) -> Vec<u8> {
+    let content = match eol {
+        None => content,
+        Some(LineEnding::Lf) => content.replace("\r\n", "\n"),
+        Some(LineEnding::Crlf) => content.replace("\r\n", "\n").replace('\n', "\r\n"),
+    };
+    let Some(encoding) = encoding else { return content.into_bytes() };
+    let text = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    match encoding {
+        TextEncoding::Utf8 => text.as_bytes().to_vec(),
+        TextEncoding::Utf8Bom => [&[0xEF, 0xBB, 0xBF][..], text.as_bytes()].concat(),
+        TextEncoding::Utf16Le => std::iter::once(0xFEFF).chain(text.encode_utf16()).flat_map(u16::to_le_bytes).collect(),
+        TextEncoding::Utf16Be => std::iter::once(0xFEFF).chain(text.encode_utf16()).flat_map(u16::to_be_bytes).collect(),
+    }
+}
+
+// Apply the permissions of `src` to `dst` (e.g. to keep the executable bit of rendered scripts).
+pub fn copy_permissions(src: &Path, dst: &Path) -> Result<()> {
+    let perms = fs::metadata(src)
+        .with_context(|| format!("Failed to read metadata: {}", src.display()))?
+        .permissions();
+    fs::set_permissions(dst, perms)
+        .with_context(|| format!("Failed to set permissions: {}", dst.display()))?;
+    Ok(())
+}
+
+// Create a symbolic link at `link` pointing to `target`. Windows needs to know
+// whether the target is a directory.
+pub fn create_symlink(target: &Path, link: &Path, is_dir: bool) -> Result<()> {
+    #[cfg(unix)]
+    let res = {
+        let _ = is_dir;
+        std::os::unix::fs::symlink(target, link)
+    };
+    #[cfg(windows)]
+    let res = if is_dir {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    };
+    res.with_context(|| format!("Failed to create symlink {} -> {}", link.display(), target.display()))?;
+    Ok(())
+}
+
+// Copy a symlink itself (not what it points to) from `src` to `dst`.
+pub fn copy_symlink(src: &Path, dst: &Path) -> Result<()> {
+    let target = fs::read_link(src)
+        .with_context(|| format!("Failed to read symlink: {}", src.display()))?;
+    let is_dir = fs::metadata(src).map(|m| m.is_dir()).unwrap_or(false);
+    if fs::symlink_metadata(dst).is_ok() {
+        fs::remove_file(dst)
+            .with_context(|| format!("Failed to replace existing file: {}", dst.display()))?;
+    }
+    create_symlink(&target, dst, is_dir)
+}
+
+// Check that a relative symlink target, resolved lexically from the directory
+// containing the link (`link_rel` is relative to the project root), stays inside the root.
+pub fn symlink_stays_within(link_rel: &Path, target: &Path) -> bool {
+    let mut depth: usize = link_rel.parent().map(|p| p.components().count()).unwrap_or(0);
+    for comp in target.components() {
+        match comp {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if depth == 0 { return false; }
+                depth -= 1;
+            }
+            Component::Normal(_) => depth += 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+// Set a Unix permission mode such as 0o755. On Windows only the read-only flag
+// is derived from the owner write bit.
+pub fn set_file_mode(path: &Path, mode: u32) -> Result<()> {
+    #[cfg(unix)]
+    let perms = {
+        use std::os::unix::fs::PermissionsExt;
+        fs::Permissions::from_mode(mode)
+    };
+    #[cfg(not(unix))]
+    let perms = {
+        let mut p = fs::metadata(path)?.permissions();
+        p.set_readonly(mode & 0o200 == 0);
+        p
+    };
+    fs::set_permissions(path, perms)
+        .with_context(|| format!("Failed to set permissions: {}", path.display()))?;
+    Ok(())
+}
 
 #[cfg(windows)]
 fn is_windows_reserved_basename(name: &str) -> bool {
@@ -105,4 +300,182 @@ pub fn safe_resolve_under_canon(canon_root: &Path, rel: &Path) -> Result<PathBuf
         }
     }
     Ok(cur)
-}
\ No newline at end of file
+}
+
+// Apply `f` to every item on a pool of up to `jobs` scoped worker threads. Items are
+// handed out in order from a shared counter; results come back in input order, so
+// callers see the same output and error ordering as a sequential loop.
+pub fn parallel_map<T: Sync, R: Send>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = jobs.min(items.len());
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let mut slots: Vec<Option<R>> = std::iter::repeat_with(|| None).take(items.len()).collect();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| scope.spawn(|| {
+                let mut done = Vec::new();
+                loop {
+                    let i = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    if i >= items.len() { break; }
+                    done.push((i, f(&items[i])));
+                }
+                done
+            }))
+            .collect();
+        for handle in handles {
+            let done = handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e));
+            for (i, r) in done {
+                slots[i] = Some(r);
+            }
+        }
+    });
+    slots.into_iter().map(|r| r.expect("every item is processed by a worker")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::SlugPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn parallel_map_keeps_input_order() {
+        let items: Vec<u64> = (0..32).collect();
+        // Early items take longest, so workers finish them after later ones
+        let results = parallel_map(&items, 4, |&i| {
+            std::thread::sleep(Duration::from_millis(32 - i));
+            (i, std::thread::current().id())
+        });
+        let order: Vec<u64> = results.iter().map(|(i, _)| *i).collect();
+        assert_eq!(order, items);
+        let mut threads: Vec<_> = results.iter().map(|(_, id)| format!("{:?}", id)).collect();
+        threads.sort();
+        threads.dedup();
+        assert!(threads.len() > 1, "expected several worker threads, got {:?}", threads);
+    }
+
+    #[test]
+    fn parallel_map_with_more_jobs_than_items() {
+        let items = ["a", "b", "c"];
+        assert_eq!(parallel_map(&items, 8, |s| s.to_uppercase()), vec!["A", "B", "C"]);
+        assert!(parallel_map(&[] as &[u8], 8, |b| *b).is_empty());
+    }
+
+    #[test]
+    fn transliterate_examples() {
+        check(transliterate, &[
+            ("Café Über", "Cafe Ueber"),
+            ("Straße", "Strasse"),
+            ("Äpfel Öl", "Aepfel Oel"),
+            ("Привет", "Privet"),
+            ("Αθήνα", "Athena"),
+            ("plain ascii_1", "plain ascii_1"),
+        ]);
+    }
+
+    #[test]
+    fn slugify_examples() {
+        assert_eq!(slugify("Café Über", '-'), "cafe-ueber");
+        assert_eq!(slugify("Café Über", '_'), "cafe_ueber");
+        assert_eq!(slugify("  Große Straße! ", '-'), "grosse-strasse");
+        assert_eq!(slugify("Привет, мир", '-'), "privet-mir");
+        assert_eq!(slugify("Ελληνικά 2", '-'), "ellenika-2");
+    }
+
+    #[test]
+    fn encode_text_line_endings() {
+        let text = || "a\nb\r\nc".to_string();
+        assert_eq!(encode_text(text(), None, None), b"a\nb\r\nc");
+        assert_eq!(encode_text(text(), Some(LineEnding::Lf), None), b"a\nb\nc");
+        // Existing CRLF is not doubled; a lone '\r' is left alone
+        assert_eq!(encode_text(text(), Some(LineEnding::Crlf), None), b"a\r\nb\r\nc");
+        assert_eq!(encode_text("x\ry\n".to_string(), Some(LineEnding::Crlf), None), b"x\ry\r\n");
+        assert_eq!(encode_text("\n\n".to_string(), Some(LineEnding::Crlf), None), b"\r\n\r\n");
+    }
+
+    #[test]
+    fn encode_text_encodings() {
+        let bom_text = || "\u{feff}hé\n".to_string();
+        // Without an encoding the text is written as is, BOM included
+        assert_eq!(encode_text(bom_text(), None, None), "\u{feff}hé\n".as_bytes());
+        assert_eq!(encode_text(bom_text(), None, Some(TextEncoding::Utf8)), "hé\n".as_bytes());
+        // A BOM is added exactly once
+        assert_eq!(encode_text("hé".to_string(), None, Some(TextEncoding::Utf8Bom)), b"\xEF\xBB\xBFh\xC3\xA9");
+        assert_eq!(encode_text(bom_text(), None, Some(TextEncoding::Utf8Bom)), b"\xEF\xBB\xBFh\xC3\xA9\n");
+        assert_eq!(encode_text(bom_text(), None, Some(TextEncoding::Utf16Le)), [0xFF, 0xFE, b'h', 0, 0xE9, 0, b'\n', 0]);
+        assert_eq!(encode_text("hé".to_string(), None, Some(TextEncoding::Utf16Be)), [0xFE, 0xFF, 0, b'h', 0, 0xE9]);
+        // Characters outside the BMP become surrogate pairs
+        assert_eq!(encode_text("😀".to_string(), None, Some(TextEncoding::Utf16Le)), [0xFF, 0xFE, 0x3D, 0xD8, 0x00, 0xDE]);
+        // Line endings are converted before encoding
+        assert_eq!(encode_text("a\n".to_string(), Some(LineEnding::Crlf), Some(TextEncoding::Utf16Le)), [0xFF, 0xFE, b'a', 0, b'\r', 0, b'\n', 0]);
+        assert_eq!(encode_text(String::new(), None, Some(TextEncoding::Utf16Be)), [0xFE, 0xFF]);
+    }
+
+    fn check(f: fn(&str) -> String, cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            assert_eq!(f(input), *expected, "input {:?}", input);
+        }
+    }
+
+    #[test]
+    fn slug_helpers() {
+        check(sanitize_slug_python, &[
+            ("My App", "my_app"),
+            ("3D Engine", "_3d_engine"),
+            ("  --hello--world--  ", "hello_world"),
+            ("already_ok", "already_ok"),
+        ]);
+        check(sanitize_slug_kebab, &[
+            ("My Service", "my-service"),
+            ("__a__b__", "a-b"),
+            ("2fa", "2fa"),
+        ]);
+        check(sanitize_slug_rust_crate, &[
+            ("My Crate", "my-crate"),
+            ("3d engine", "_3d-engine"),
+            ("_private_lib_", "private_lib"),
+            ("serde.json", "serde-json"),
+        ]);
+        check(sanitize_slug_npm, &[
+            ("My Package", "my-package"),
+            (".hidden", "hidden"),
+            ("_internal", "internal"),
+            ("a.b_c~d", "a.b_c~d"),
+            ("@scope/pkg", "scope-pkg"),
+        ]);
+        check(sanitize_slug_go_module, &[
+            ("My Module", "my-module"),
+            (".config.", "config"),
+            ("v2_api~x", "v2_api~x"),
+        ]);
+    }
+
+    #[test]
+    fn npm_slug_is_limited_to_214_characters() {
+        assert_eq!(sanitize_slug_npm(&"a".repeat(300)), "a".repeat(214));
+        // Truncation never leaves a trailing separator
+        let input = format!("{} b", "a".repeat(213));
+        assert_eq!(sanitize_slug_npm(&input), "a".repeat(213));
+    }
+
+    #[test]
+    fn slug_policies() {
+        let custom = SlugPolicy::parse(&serde_json::json!({ "pattern": "[^a-z0-9.]+", "replacement": "." })).unwrap();
+        let verbatim = SlugPolicy::parse(&serde_json::json!({ "pattern": "\\s+", "lowercase": false, "transliterate": false })).unwrap();
+        let cases = [
+            (SlugPolicy::Python, "Café Über 2", "cafe_ueber_2"),
+            (SlugPolicy::Kebab, "Café Über 2", "cafe-ueber-2"),
+            (SlugPolicy::RustCrate, "3 Body", "_3-body"),
+            (SlugPolicy::Npm, "_Über", "ueber"),
+            (SlugPolicy::GoModule, ".Gö.", "goe"),
+            (SlugPolicy::None, "  Café Über  ", "Café Über"),
+            (custom, "My App 2", "my.app.2"),
+            (verbatim, " Café  Über ", "Café_Über"),
+        ];
+        for (policy, input, expected) in cases {
+            assert_eq!(policy.apply(input), expected, "{:?} on {:?}", policy, input);
+        }
+    }
+}
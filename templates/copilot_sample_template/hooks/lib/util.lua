@@ -0,0 +1,15 @@
+-- lib/util.lua
+-- Shared helpers for hook scripts. Load with `local util = require("lib.util")`.
+
+local util = {}
+
+function util.slugify(s)
+  s = string.lower(s or "")
+  s = s:gsub("%s+", "-")       -- spaces -> '-'
+  s = s:gsub("_+", "-")        -- underscores -> '-'
+  s = s:gsub("[^a-z0-9%-]", "") -- strip non-alnum except '-'
+  s = s:gsub("%-+", "-")        -- collapse multiple '-'
+  return s
+end
+
+return util
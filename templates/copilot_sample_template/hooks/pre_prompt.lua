@@ -2,14 +2,7 @@
 -- Goal: adjust default variables before interactive prompts.
 -- You can compute derived values or change defaults based on existing ones.
 
-local function slugify(s)
-  s = string.lower(s or "")
-  s = s:gsub("%s+", "-")       -- spaces -> '-'
-  s = s:gsub("_+", "-")        -- underscores -> '-'
-  s = s:gsub("[^a-z0-9%-]", "") -- strip non-alnum except '-'
-  s = s:gsub("%-+", "-")        -- collapse multiple '-'
-  return s
-end
+local util = require("lib.util")
 
 local name = vars.project_name or "project"
 local author = vars.author or "Unknown"
@@ -21,9 +14,15 @@ local default_license = is_private and "GPL-3.0" or "MIT"
 
 return {
   vars = {
-    project_slug = slugify(name),
+    project_slug = util.slugify(name),
     readme_title = name .. " by " .. author,
     license = default_license,
     retries = math.max(1, retries),
-  }
+  },
+  -- Optional: reshape the question list, e.g.
+  -- questions = {
+  --   hide = { "email" },
+  --   order = { "project_title", "project_slug", "description", "author", "license", "language_code" },
+  --   add = { { name = "team", choices = { "core", "infra" }, default = "core" } },
+  -- },
 }
\ No newline at end of file